@@ -1,15 +1,37 @@
 #[tokio::main]
-async fn main() -> eyre::Result<()> {
+async fn main() -> eyre::Result<std::process::ExitCode> {
     color_eyre::install()?;
     tracing_subscriber::fmt()
-        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::builder()
+                .with_default_directive(tracing::level_filters::LevelFilter::WARN.into())
+                .from_env_lossy(),
+        )
+        .with_writer(std::io::stderr)
         .init();
 
-    cli::run().await
+    match cli::run().await {
+        Ok(()) => Ok(std::process::ExitCode::SUCCESS),
+        Err(e) => {
+            // Platform errors get a distinct exit code per kind so scripts can branch on it
+            let kind = e
+                .chain()
+                .find_map(|cause| cause.downcast_ref::<post_core::Error>());
+            match kind {
+                Some(kind) => {
+                    eprintln!("Error: {e:?}");
+                    Ok(std::process::ExitCode::from(kind.exit_code()))
+                }
+                None => Err(e),
+            }
+        }
+    }
 }
 
 mod cli {
     use clap::Parser as _;
+    use eyre::WrapErr as _;
+    use post_core::Platform as _;
 
     #[derive(clap::Parser)]
     #[command(name = "post")]
@@ -24,14 +46,57 @@ mod cli {
     enum Command {
         /// Post to X (Twitter)
         X {
+            /// Saved account to use (default: the default account)
+            #[arg(long, global = true)]
+            account: Option<String>,
+
             #[command(subcommand)]
             command: XCommand,
         },
         /// Post to Reddit
         Reddit {
+            /// Saved account to use (default: the default account)
+            #[arg(long, global = true)]
+            account: Option<String>,
+
             #[command(subcommand)]
             command: RedditCommand,
         },
+        /// Delete the most recent post made with this tool
+        Undo,
+        /// Post the same content to several platforms at once
+        All {
+            /// Text to post (the tweet on X, the title on Reddit)
+            text: String,
+
+            /// Platforms to post to, comma-separated (default: every authenticated platform)
+            #[arg(long, value_delimiter = ',')]
+            to: Vec<String>,
+
+            /// Target subreddit for Reddit (without r/)
+            #[arg(short = 'r', long)]
+            subreddit: Option<String>,
+
+            /// Body text, for platforms that support it (on Reddit link posts, the first comment)
+            #[arg(short, long)]
+            body: Option<String>,
+
+            /// URL to share
+            #[arg(short, long)]
+            link: Option<String>,
+
+            /// Saved account to use on every platform (default: each platform's default account)
+            #[arg(long)]
+            account: Option<String>,
+
+            /// Post without checking platform requirements first (e.g. subreddit rules)
+            #[arg(long)]
+            skip_checks: bool,
+
+            /// Post the link even where it was already posted (e.g. to the subreddit)
+            #[arg(long, requires = "link")]
+            force: bool,
+        },
     }
 
     #[derive(clap::Subcommand)]
@@ -45,137 +110,944 @@ mod cli {
             /// Client Secret from X Developer Portal
             #[arg(long, env = "X_CLIENT_SECRET")]
             client_secret: String,
+
+            #[command(flatten)]
+            callback: CallbackArgs,
+
+            /// Don't open a browser; print the authorization URL and paste the redirect URL back
+            #[arg(long)]
+            no_browser: bool,
         },
         /// Post a tweet
-        Post {
-            /// Text to post
-            text: String,
+        Post(XPost),
+        /// Delete a tweet
+        Delete {
+            /// Tweet to delete, as a URL or ID
+            target: String,
+        },
+        /// Post a thread of tweets, each replying to the previous one
+        Thread {
+            /// Tweets in order
+            #[arg(required_unless_present = "file", conflicts_with = "file")]
+            texts: Vec<String>,
+
+            /// Read the tweets from a file, separated by lines containing only `---`
+            #[arg(short, long, value_name = "PATH")]
+            file: Option<std::path::PathBuf>,
+
+            /// Continue an existing thread by replying to this tweet, given as a URL or ID
+            #[arg(long, value_name = "TWEET")]
+            reply_to: Option<String>,
+
+            /// Start from this tweet (1-based), to resume a thread that failed part way
+            #[arg(long, default_value_t = 1)]
+            start: usize,
         },
         /// Show current auth status
         Status,
         /// Clear saved credentials
         Logout,
+        /// List and manage saved accounts
+        Accounts {
+            #[command(subcommand)]
+            command: Option<AccountsCommand>,
+        },
     }
 
     #[derive(clap::Subcommand)]
     enum RedditCommand {
         /// Authenticate with Reddit
-        Auth {
-            /// Client ID from Reddit (reddit.com/prefs/apps)
-            #[arg(long, env = "REDDIT_CLIENT_ID")]
-            client_id: String,
-
-            /// Client Secret from Reddit
-            #[arg(long, env = "REDDIT_CLIENT_SECRET")]
-            client_secret: String,
-
-            /// Reddit username
-            #[arg(long, env = "REDDIT_USERNAME")]
-            username: String,
+        Auth(RedditAuth),
+        /// Submit a post to a subreddit
+        Post(RedditPost),
+        /// Comment on a post, or reply to a comment
+        Comment {
+            /// Post or comment to reply to, as a Reddit URL or ID
+            target: String,
 
-            /// Reddit password
-            #[arg(long, env = "REDDIT_PASSWORD")]
-            password: String,
+            /// Comment text (Markdown)
+            text: String,
         },
-        /// Submit a post to a subreddit
-        Post {
-            /// Target subreddit (without r/)
-            #[arg(short, long)]
-            subreddit: String,
+        /// Change the text of a self post or comment
+        Edit {
+            /// Post or comment to edit, as a Reddit URL or ID
+            target: String,
 
-            /// Post title
+            /// New text (Markdown)
             #[arg(short, long)]
-            title: String,
+            body: String,
+        },
+        /// Delete a post or comment
+        Delete {
+            /// Post or comment to delete, as a Reddit URL or ID
+            target: String,
+        },
+        /// Share an existing post into another subreddit
+        Crosspost {
+            /// Post to share, as a Reddit URL or ID
+            source: String,
 
-            /// Post body text (optional for self posts)
-            #[arg(short, long)]
-            body: Option<String>,
+            /// Subreddit to share into (without r/)
+            #[arg(short = 'r', long)]
+            subreddit: String,
 
-            /// URL to submit as link post (mutually exclusive with body)
-            #[arg(short, long, conflicts_with = "body")]
-            link: Option<String>,
+            /// Title (default: the original post's title)
+            #[arg(short, long)]
+            title: Option<String>,
+        },
+        /// Show a subreddit's rules and post requirements
+        Rules {
+            /// Subreddit (without r/)
+            #[arg(short = 'r', long)]
+            subreddit: String,
+        },
+        /// List the link flairs a subreddit offers
+        Flairs {
+            /// Subreddit (without r/)
+            #[arg(short = 'r', long)]
+            subreddit: String,
         },
         /// Show current auth status
         Status,
         /// Clear saved credentials
         Logout,
+        /// List and manage saved accounts
+        Accounts {
+            #[command(subcommand)]
+            command: Option<AccountsCommand>,
+        },
+    }
+
+    /// Where to listen for the OAuth redirect (default: config, then
+    /// http://localhost:8080/callback)
+    #[derive(clap::Args)]
+    struct CallbackArgs {
+        /// Host in the redirect URI
+        #[arg(long = "callback-host", env = "POST_CALLBACK_HOST")]
+        host: Option<String>,
+
+        /// Port to listen on; 0 picks a free port (only if the provider accepts any port)
+        #[arg(long = "callback-port", env = "POST_CALLBACK_PORT")]
+        port: Option<u16>,
+
+        /// Path in the redirect URI
+        #[arg(long = "callback-path", env = "POST_CALLBACK_PATH")]
+        path: Option<String>,
+    }
+
+    impl From<CallbackArgs> for post_core::CallbackConfig {
+        fn from(args: CallbackArgs) -> Self {
+            Self {
+                host: args.host,
+                port: args.port,
+                path: args.path,
+            }
+        }
+    }
+
+    #[derive(clap::Args)]
+    struct XPost {
+        /// Text to post
+        text: String,
+
+        /// Image, GIF or video to attach (repeat for up to four images)
+        #[arg(long, value_name = "PATH")]
+        media: Vec<std::path::PathBuf>,
+
+        /// Alt text for the attached media, in the same order as --media
+        #[arg(long, requires = "media")]
+        alt: Vec<String>,
+
+        /// Reply to this tweet, given as a URL or ID
+        #[arg(long, value_name = "TWEET")]
+        reply_to: Option<String>,
+
+        /// Quote this tweet, given as a URL or ID
+        #[arg(long, value_name = "TWEET")]
+        quote: Option<String>,
+
+        /// Limit who can reply: `following` or `mentionedUsers`
+        #[arg(long)]
+        reply_settings: Option<post_x::ReplySettings>,
+
+        /// Poll option (repeat for 2 to 4 options)
+        #[arg(long, value_name = "OPTION", conflicts_with_all = ["media", "quote"])]
+        poll: Vec<String>,
+
+        /// How long the poll runs, e.g. 30m, 12h or 1d
+        #[arg(long, requires = "poll", default_value = "1d", value_parser = post_x::poll_duration)]
+        poll_duration: u32,
+    }
+
+    #[derive(clap::Args)]
+    #[command(group(clap::ArgGroup::new("browser_login").args(["browser", "no_browser"])))]
+    struct RedditAuth {
+        /// Client ID from Reddit (reddit.com/prefs/apps)
+        #[arg(long, env = "REDDIT_CLIENT_ID")]
+        client_id: String,
+
+        /// Client Secret from Reddit; leave out for "installed app" types
+        #[arg(
+            long,
+            env = "REDDIT_CLIENT_SECRET",
+            default_value = "",
+            hide_default_value = true
+        )]
+        client_secret: String,
+
+        /// Reddit username
+        #[arg(long, env = "REDDIT_USERNAME", required_unless_present_any = ["browser", "no_browser"])]
+        username: Option<String>,
+
+        /// Reddit password
+        #[arg(long, env = "REDDIT_PASSWORD", required_unless_present_any = ["browser", "no_browser"])]
+        password: Option<String>,
+
+        /// Two-factor code from your authenticator app; asked for if Reddit rejects the login
+        #[arg(long, conflicts_with = "browser_login")]
+        otp: Option<String>,
+
+        /// Authorize in a browser instead of with a password, for "web app" and "installed app" types
+        #[arg(long)]
+        browser: bool,
+
+        /// Like --browser, but print the authorization URL and paste the redirect URL back
+        #[arg(long)]
+        no_browser: bool,
+
+        /// OAuth scopes to request with --browser (default: identity,submit,edit,read,flair,history)
+        #[arg(long, value_delimiter = ',', requires = "browser_login")]
+        scopes: Vec<String>,
+
+        #[command(flatten)]
+        callback: CallbackArgs,
+    }
+
+    impl From<RedditAuth> for post_core::Credentials {
+        fn from(args: RedditAuth) -> Self {
+            Self {
+                client_id: args.client_id,
+                client_secret: args.client_secret,
+                username: args.username,
+                password: args.password,
+                otp: args.otp,
+                callback: args.callback.into(),
+                browser: args.browser,
+                no_browser: args.no_browser,
+                scopes: args.scopes,
+            }
+        }
+    }
+
+    #[derive(clap::Args)]
+    struct RedditPost {
+        /// Target subreddit (without r/)
+        #[arg(short = 'r', long, short_alias = 's')]
+        subreddit: String,
+
+        /// Post title
+        #[arg(short, long)]
+        title: String,
+
+        /// Post body text (optional for self posts)
+        #[arg(short, long)]
+        body: Option<String>,
+
+        /// URL to submit as link post (mutually exclusive with body)
+        #[arg(short, long, conflicts_with = "body")]
+        link: Option<String>,
+
+        /// Image to post (repeat for a gallery of up to 20)
+        #[arg(long, value_name = "PATH", conflicts_with_all = ["body", "link", "video"])]
+        image: Vec<std::path::PathBuf>,
+
+        /// Gallery caption, in the same order as --image
+        #[arg(long, requires = "image")]
+        caption: Vec<String>,
+
+        /// Gallery link shown with each image, in the same order as --image
+        #[arg(long, requires = "image")]
+        outbound_url: Vec<String>,
+
+        /// Video to post
+        #[arg(long, value_name = "PATH", conflicts_with_all = ["body", "link"], requires = "poster")]
+        video: Option<std::path::PathBuf>,
+
+        /// Thumbnail image shown before the video plays
+        #[arg(long, value_name = "PATH", requires = "video")]
+        poster: Option<std::path::PathBuf>,
+
+        /// Flair to attach, by name or template ID (see `post reddit flairs`)
+        #[arg(long)]
+        flair: Option<String>,
+
+        /// Custom flair text, for flairs that allow editing
+        #[arg(long, requires = "flair")]
+        flair_text: Option<String>,
+
+        /// Mark the post as not safe for work
+        #[arg(long)]
+        nsfw: bool,
+
+        /// Mark the post as a spoiler
+        #[arg(long)]
+        spoiler: bool,
+
+        /// Don't send comment replies to your inbox
+        #[arg(long)]
+        no_replies: bool,
+
+        /// Tag the post as original content
+        #[arg(long)]
+        oc: bool,
+
+        /// Comment to add right after the post is submitted
+        #[arg(long, value_name = "TEXT")]
+        first_comment: Option<String>,
+
+        /// Submit a link even if it was already posted to the subreddit
+        #[arg(long, alias = "resubmit", requires = "link")]
+        force: bool,
+
+        /// Submit without checking the subreddit's post requirements first
+        #[arg(long)]
+        skip_checks: bool,
+    }
+
+    #[derive(clap::Subcommand)]
+    enum AccountsCommand {
+        /// List saved accounts, marking the default with `*`
+        List,
+        /// Rename a saved account
+        Rename {
+            /// Current account name
+            from: String,
+            /// New account name
+            to: String,
+        },
+        /// Set the account used when --account is not given
+        Default {
+            /// Account name
+            name: String,
+        },
     }
 
     pub async fn run() -> eyre::Result<()> {
         let cli = Cli::parse();
 
         match cli.command {
-            Command::X { command } => handle_x(command).await,
-            Command::Reddit { command } => handle_reddit(command).await,
+            Command::X { account, command } => handle_x(account, command).await,
+            Command::Reddit { account, command } => handle_reddit(account, command).await,
+            Command::Undo => undo().await,
+            Command::All {
+                text,
+                to,
+                subreddit,
+                body,
+                link,
+                account,
+                skip_checks,
+                force,
+            } => {
+                let content = post_core::Content {
+                    text,
+                    body,
+                    link,
+                    community: subreddit,
+                    skip_checks,
+                    force,
+                };
+                handle_all(&to, account, &content).await
+            }
         }
     }
 
-    async fn handle_x(command: XCommand) -> eyre::Result<()> {
+    fn platforms(account: Option<String>) -> Vec<Box<dyn post_core::Platform>> {
+        vec![
+            Box::new(post_x::X::new(account.clone())),
+            Box::new(post_reddit::Reddit::new(account)),
+        ]
+    }
+
+    async fn handle_all(
+        to: &[String],
+        account: Option<String>,
+        content: &post_core::Content,
+    ) -> eyre::Result<()> {
+        let mut targets = platforms(account);
+        if to.is_empty() {
+            targets.retain(|platform| match platform.status() {
+                Ok(status) => status.is_some(),
+                Err(e) => {
+                    tracing::warn!("skipping {}: {e:#}", platform.display_name());
+                    false
+                }
+            });
+            if targets.is_empty() {
+                eyre::bail!(
+                    "not authenticated with any platform — run `post <platform> auth` first"
+                );
+            }
+        } else {
+            for name in to {
+                if !targets.iter().any(|platform| platform.name() == name) {
+                    eyre::bail!("unknown platform `{name}`");
+                }
+            }
+            targets.retain(|platform| to.iter().any(|name| name == platform.name()));
+        }
+
+        let results = futures::future::join_all(targets.iter().map(|platform| async move {
+            platform.capabilities().check(content)?;
+            platform.publish(content).await
+        }))
+        .await;
+
+        let mut failed = Vec::new();
+        for (platform, result) in targets.iter().zip(results) {
+            match result {
+                Ok(result) => {
+                    println!("{}: {}", platform.display_name(), result.url);
+                    record(platform.as_ref(), &result);
+                }
+                Err(e) => {
+                    println!("{}: failed — {e:#}", platform.display_name());
+                    failed.push(platform.display_name());
+                }
+            }
+        }
+
+        if !failed.is_empty() {
+            eyre::bail!(
+                "posted to {} of {} platforms; failed: {}",
+                targets.len() - failed.len(),
+                targets.len(),
+                failed.join(", ")
+            );
+        }
+        Ok(())
+    }
+
+    async fn handle_x(account: Option<String>, command: XCommand) -> eyre::Result<()> {
+        let platform = post_x::X::new(account);
         match command {
             XCommand::Auth {
                 client_id,
                 client_secret,
+                callback,
+                no_browser,
             } => {
-                post_x::authenticate(&client_id, &client_secret).await?;
+                let credentials = post_core::Credentials {
+                    client_id,
+                    client_secret,
+                    callback: callback.into(),
+                    no_browser,
+                    ..Default::default()
+                };
+                platform.authenticate(&credentials).await?;
                 println!("Authentication successful!");
                 Ok(())
             }
-            XCommand::Post { text } => {
-                let result = post_x::post(&text).await?;
-                println!("Posted: {}", result.url);
+            XCommand::Post(post) => x_post(&platform, post).await,
+            XCommand::Delete { target } => {
+                delete(&platform, &post_x::tweet_id(&target)?).await?;
+                println!("Deleted: {target}");
                 Ok(())
             }
-            XCommand::Status => {
-                match post_x::status() {
-                    Some(msg) => println!("{msg}"),
-                    None => println!("Not authenticated with X — run `post x auth` first"),
-                }
-                Ok(())
-            }
-            XCommand::Logout => {
-                post_x::logout()?;
-                println!("Logged out of X");
-                Ok(())
+            XCommand::Thread {
+                texts,
+                file,
+                reply_to,
+                start,
+            } => thread(&platform, texts, file, reply_to, start).await,
+            XCommand::Status => status(&platform),
+            XCommand::Logout => logout(&platform),
+            XCommand::Accounts { command } => {
+                manage_accounts(command, &platform, |config| &mut config.x)
             }
         }
     }
 
-    async fn handle_reddit(command: RedditCommand) -> eyre::Result<()> {
+    async fn handle_reddit(account: Option<String>, command: RedditCommand) -> eyre::Result<()> {
+        let platform = post_reddit::Reddit::new(account);
         match command {
-            RedditCommand::Auth {
-                client_id,
-                client_secret,
-                username,
-                password,
-            } => {
-                post_reddit::auth(&client_id, &client_secret, &username, &password).await?;
+            RedditCommand::Auth(auth) => {
+                platform.authenticate(&auth.into()).await?;
                 println!("Authentication successful!");
                 Ok(())
             }
-            RedditCommand::Post {
+            RedditCommand::Post(post) => reddit_post(&platform, post).await,
+            RedditCommand::Comment { target, text } => {
+                let result = platform.comment(&target, &text).await?;
+                println!("Commented: {}", result.url);
+                record(&platform, &result);
+                Ok(())
+            }
+            RedditCommand::Edit { target, body } => {
+                platform.edit(&target, &body).await?;
+                println!("Edited: {target}");
+                Ok(())
+            }
+            RedditCommand::Delete { target } => {
+                delete(&platform, &post_reddit::fullname(&target)?).await?;
+                println!("Deleted: {target}");
+                Ok(())
+            }
+            RedditCommand::Crosspost {
+                source,
                 subreddit,
                 title,
-                body,
-                link,
             } => {
-                let result = if let Some(url) = link {
-                    post_reddit::post_link(&subreddit, &title, &url).await?
-                } else {
-                    post_reddit::post(&subreddit, &title, body.as_deref()).await?
-                };
+                let result = platform
+                    .crosspost(&source, &subreddit, title.as_deref())
+                    .await?;
                 println!("Posted: {}", result.url);
+                record(&platform, &result);
+                Ok(())
+            }
+            RedditCommand::Rules { subreddit } => {
+                let (rules, requirements) = platform.rules(&subreddit).await?;
+                if rules.is_empty() {
+                    println!("r/{subreddit} has no rules");
+                }
+                for (number, rule) in rules.iter().enumerate() {
+                    println!("{}. {}", number + 1, rule.short_name);
+                    for line in rule
+                        .description
+                        .lines()
+                        .filter(|line| !line.trim().is_empty())
+                    {
+                        println!("   {line}");
+                    }
+                }
+                print_requirements(&requirements);
+                Ok(())
+            }
+            RedditCommand::Flairs { subreddit } => {
+                let flairs = platform.flairs(&subreddit).await?;
+                if flairs.is_empty() {
+                    println!("r/{subreddit} has no link flairs");
+                }
+                for flair in flairs {
+                    let mut notes = Vec::new();
+                    if flair.text_editable {
+                        notes.push("editable text");
+                    }
+                    if flair.mod_only {
+                        notes.push("mods only");
+                    }
+                    if notes.is_empty() {
+                        println!("{}  {}", flair.id, flair.text);
+                    } else {
+                        println!("{}  {} ({})", flair.id, flair.text, notes.join(", "));
+                    }
+                }
+                Ok(())
+            }
+            RedditCommand::Status => status(&platform),
+            RedditCommand::Logout => logout(&platform),
+            RedditCommand::Accounts { command } => {
+                manage_accounts(command, &platform, |config| &mut config.reddit)
+            }
+        }
+    }
+
+    async fn x_post(platform: &post_x::X, post: XPost) -> eyre::Result<()> {
+        let XPost {
+            text,
+            media,
+            alt,
+            reply_to,
+            quote,
+            reply_settings,
+            poll,
+            poll_duration,
+        } = post;
+
+        if alt.len() > media.len() {
+            eyre::bail!(
+                "got {} --alt texts for {} --media files",
+                alt.len(),
+                media.len()
+            );
+        }
+        let reply = reply_to
+            .map(|tweet| post_x::tweet_id(&tweet))
+            .transpose()?
+            .map(|in_reply_to_tweet_id| post_x::Reply {
+                in_reply_to_tweet_id,
+            });
+        let quote_tweet_id = quote.map(|tweet| post_x::tweet_id(&tweet)).transpose()?;
+        let poll = if poll.is_empty() {
+            None
+        } else {
+            Some(post_x::Poll::new(poll, poll_duration)?)
+        };
+
+        let mut alt = alt.into_iter();
+        let files: Vec<_> = media
+            .into_iter()
+            .map(|path| post_x::MediaFile {
+                path,
+                alt: alt.next(),
+            })
+            .collect();
+        let media_ids = platform.upload_media(&files).await?;
+
+        let request = post_x::TweetRequest {
+            text,
+            reply,
+            media: (!media_ids.is_empty()).then_some(post_x::Media { media_ids }),
+            poll,
+            quote_tweet_id,
+            reply_settings,
+        };
+        let result = platform.tweet(&request).await?;
+        println!("Posted: {}", result.url);
+        record(platform, &result);
+        Ok(())
+    }
+
+    async fn reddit_post(platform: &post_reddit::Reddit, post: RedditPost) -> eyre::Result<()> {
+        let RedditPost {
+            subreddit,
+            title,
+            body,
+            link,
+            image,
+            caption,
+            outbound_url,
+            video,
+            poster,
+            flair,
+            flair_text,
+            nsfw,
+            spoiler,
+            no_replies,
+            oc,
+            first_comment,
+            force,
+            skip_checks,
+        } = post;
+
+        let flair = match flair {
+            Some(flair) => Some(
+                platform
+                    .resolve_flair(&subreddit, &flair, flair_text)
+                    .await?,
+            ),
+            None => None,
+        };
+        let options = post_reddit::SubmitOptions {
+            flair,
+            nsfw,
+            spoiler,
+            send_replies: !no_replies,
+            resubmit: force,
+            original_content: oc,
+        };
+
+        let images = gallery(image, caption, outbound_url)?;
+
+        if !skip_checks {
+            let kind = match (&link, images.as_slice(), &video) {
+                (_, _, Some(_)) => post_reddit::DraftKind::Video,
+                (Some(link), [], None) => post_reddit::DraftKind::Link(link),
+                (None, [], None) => post_reddit::DraftKind::Text(body.as_deref()),
+                (_, images, None) if post_reddit::MediaFile::is_gallery(images) => {
+                    post_reddit::DraftKind::Gallery(images)
+                }
+                (_, _, None) => post_reddit::DraftKind::Image,
+            };
+            platform
+                .check(&post_reddit::Draft {
+                    subreddit: &subreddit,
+                    title: &title,
+                    kind,
+                    flair: options.flair.is_some(),
+                })
+                .await?;
+        }
+
+        if let Some(link) = &link
+            && !force
+        {
+            platform.refuse_reposts(&subreddit, link).await?;
+        }
+
+        let result = if let (Some(video), Some(poster)) = (video, poster) {
+            platform
+                .submit_video(&subreddit, &title, &video, &poster, &options)
+                .await?
+        } else if images.is_empty() {
+            let content = post_core::Content {
+                text: title,
+                body,
+                link,
+                community: Some(subreddit),
+                ..Default::default()
+            };
+            platform.submit(&content, &options).await?
+        } else {
+            platform
+                .submit_images(&subreddit, &title, &images, &options)
+                .await?
+        };
+        println!("Posted: {}", result.url);
+        record(platform, &result);
+
+        if let Some(text) = first_comment {
+            let comment = platform
+                .comment(&result.id, &text)
+                .await
+                .wrap_err("posted, but failed to add the first comment")?;
+            println!("Commented: {}", comment.url);
+            record(platform, &comment);
+        }
+        Ok(())
+    }
+
+    /// Pair each image with its caption and link, given in the same order.
+    fn gallery(
+        images: Vec<std::path::PathBuf>,
+        captions: Vec<String>,
+        outbound_urls: Vec<String>,
+    ) -> eyre::Result<Vec<post_reddit::MediaFile>> {
+        if captions.len() > images.len() || outbound_urls.len() > images.len() {
+            eyre::bail!(
+                "got more --caption or --outbound-url values than {} --image files",
+                images.len()
+            );
+        }
+        let (mut captions, mut outbound_urls) = (captions.into_iter(), outbound_urls.into_iter());
+        Ok(images
+            .into_iter()
+            .map(|path| post_reddit::MediaFile {
+                path,
+                caption: captions.next(),
+                outbound_url: outbound_urls.next(),
+            })
+            .collect())
+    }
+
+    fn print_requirements(requirements: &post_reddit::PostRequirements) {
+        let mut lines = Vec::new();
+        match (
+            requirements.title_text_min_length,
+            requirements.title_text_max_length,
+        ) {
+            (Some(min), Some(max)) => {
+                lines.push(format!("title between {min} and {max} characters"));
+            }
+            (Some(min), None) => lines.push(format!("title at least {min} characters")),
+            (None, Some(max)) => lines.push(format!("title at most {max} characters")),
+            (None, None) => {}
+        }
+        if !requirements.title_required_strings.is_empty() {
+            lines.push(format!(
+                "title must contain one of: {}",
+                requirements.title_required_strings.join(", ")
+            ));
+        }
+        if !requirements.title_blacklisted_strings.is_empty() {
+            lines.push(format!(
+                "title must not contain: {}",
+                requirements.title_blacklisted_strings.join(", ")
+            ));
+        }
+        match requirements.body_restriction_policy.as_deref() {
+            Some("required") => lines.push("text posts need a body".to_string()),
+            Some("notAllowed") => lines.push("text posts must not have a body".to_string()),
+            _ => {}
+        }
+        match requirements.link_restriction_policy.as_deref() {
+            Some("whitelist") => lines.push(format!(
+                "links only to: {}",
+                requirements.domain_whitelist.join(", ")
+            )),
+            Some("blacklist") => lines.push(format!(
+                "no links to: {}",
+                requirements.domain_blacklist.join(", ")
+            )),
+            _ => {}
+        }
+        if requirements.is_flair_required {
+            lines.push("flair required".to_string());
+        }
+
+        if !lines.is_empty() {
+            println!("\nPost requirements:");
+            for line in lines {
+                println!("  - {line}");
+            }
+        }
+        if let Some(guidelines) = &requirements.guidelines_text {
+            println!("\nGuidelines:\n{guidelines}");
+        }
+    }
+
+    async fn thread(
+        platform: &post_x::X,
+        texts: Vec<String>,
+        file: Option<std::path::PathBuf>,
+        reply_to: Option<String>,
+        start: usize,
+    ) -> eyre::Result<()> {
+        let mut tweets = match file {
+            Some(path) => {
+                let contents = std::fs::read_to_string(&path)
+                    .wrap_err_with(|| format!("failed to read thread from {}", path.display()))?;
+                post_x::split_thread(&contents)
+            }
+            None => {
+                if let [text] = texts.as_slice()
+                    && std::path::Path::new(text).exists()
+                {
+                    eyre::bail!("`{text}` is a file — use `--file {text}` to post its contents");
+                }
+                texts
+            }
+        };
+        let total = tweets.len();
+        if total == 0 {
+            eyre::bail!("thread is empty");
+        }
+        if start == 0 || start > total {
+            eyre::bail!("--start must be between 1 and {total}");
+        }
+        let skipped = start - 1;
+        tweets.drain(..skipped);
+        let reply_to = reply_to.map(|tweet| post_x::tweet_id(&tweet)).transpose()?;
+
+        match platform.thread(&tweets, reply_to.clone()).await {
+            Ok(posted) => {
+                for (index, result) in posted.iter().enumerate() {
+                    println!("Posted {}/{total}: {}", skipped + index + 1, result.url);
+                    record(platform, result);
+                }
                 Ok(())
             }
-            RedditCommand::Status => {
-                match post_reddit::status() {
-                    Some(msg) => println!("{msg}"),
-                    None => println!("Not authenticated with Reddit — run `post reddit auth` first"),
+            Err(e) => {
+                for (index, result) in e.posted.iter().enumerate() {
+                    println!("Posted {}/{total}: {}", skipped + index + 1, result.url);
+                    record(platform, result);
+                }
+                let failed = skipped + e.failed + 1;
+                // Nothing new was posted, so the resumed thread still replies to the original tweet
+                let resume = match e.posted.last().map(|last| &last.id).or(reply_to.as_ref()) {
+                    Some(id) => format!("--start {failed} --reply-to {id}"),
+                    None => format!("--start {failed}"),
+                };
+                Err(e.error.wrap_err(format!(
+                    "tweet {failed} of {total} failed — resume with `{resume}`"
+                )))
+            }
+        }
+    }
+
+    /// Remember a post for `post undo`. The post already succeeded, so a
+    /// failure here is only a warning.
+    fn record(platform: &dyn post_core::Platform, result: &post_core::PostResult) {
+        if let Err(e) = post_core::History::record(platform, result) {
+            tracing::warn!("failed to record {} in history: {e:#}", result.url);
+        }
+    }
+
+    /// Delete a post and drop it from the history.
+    async fn delete(platform: &dyn post_core::Platform, id: &str) -> eyre::Result<()> {
+        platform.delete(id).await?;
+        post_core::History::forget(platform.name(), id)?;
+        Ok(())
+    }
+
+    /// Delete the most recent post made with this tool.
+    async fn undo() -> eyre::Result<()> {
+        let history = post_core::History::load()?;
+        let Some(last) = history.last() else {
+            eyre::bail!("nothing to undo — no posts recorded yet");
+        };
+        let platform = platforms(last.account.clone())
+            .into_iter()
+            .find(|platform| platform.name() == last.platform)
+            .ok_or_else(|| eyre::eyre!("unknown platform `{}` in history", last.platform))?;
+
+        match delete(platform.as_ref(), &last.id).await {
+            Ok(()) => println!("Deleted: {}", last.url),
+            // Deleted some other way; drop it so the next undo moves on
+            Err(e)
+                if e.chain().any(|cause| {
+                    matches!(
+                        cause.downcast_ref::<post_core::Error>(),
+                        Some(post_core::Error::NotFound(_))
+                    )
+                }) =>
+            {
+                post_core::History::forget(&last.platform, &last.id)?;
+                println!("Already deleted: {}", last.url);
+            }
+            Err(e) => return Err(e.wrap_err(format!("failed to delete {}", last.url))),
+        }
+        Ok(())
+    }
+
+    fn status(platform: &dyn post_core::Platform) -> eyre::Result<()> {
+        match platform.status()? {
+            Some(msg) => println!("{msg}"),
+            None => println!(
+                "Not authenticated with {} — run `post {} auth` first",
+                platform.display_name(),
+                platform.name()
+            ),
+        }
+        Ok(())
+    }
+
+    fn logout(platform: &dyn post_core::Platform) -> eyre::Result<()> {
+        platform.logout()?;
+        println!("Logged out of {}", platform.display_name());
+        Ok(())
+    }
+
+    fn manage_accounts<T>(
+        command: Option<AccountsCommand>,
+        platform: &dyn post_core::Platform,
+        select: impl Fn(&mut post_core::Config) -> &mut post_core::Accounts<T>,
+    ) -> eyre::Result<()> {
+        let mut config = post_core::Config::load()?;
+        let accounts = select(&mut config);
+
+        match command.unwrap_or(AccountsCommand::List) {
+            AccountsCommand::List => {
+                if accounts.is_empty() {
+                    println!(
+                        "No {} accounts — run `post {} auth` first",
+                        platform.display_name(),
+                        platform.name()
+                    );
                 }
+                let default = accounts.resolve(None);
+                for name in accounts.names() {
+                    let marker = if default == Some(name) { "*" } else { " " };
+                    println!("{marker} {name}");
+                }
+                Ok(())
+            }
+            AccountsCommand::Rename { from, to } => {
+                accounts.rename(&from, &to)?;
+                config.save()?;
+                println!(
+                    "Renamed {} account `{from}` to `{to}`",
+                    platform.display_name()
+                );
                 Ok(())
             }
-            RedditCommand::Logout => {
-                post_reddit::logout()?;
-                println!("Logged out of Reddit");
+            AccountsCommand::Default { name } => {
+                accounts.set_default(&name)?;
+                config.save()?;
+                println!(
+                    "Default {} account is now `{name}`",
+                    platform.display_name()
+                );
                 Ok(())
             }
         }
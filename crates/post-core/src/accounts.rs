@@ -0,0 +1,225 @@
+/// Name given to an account when none is specified
+pub const DEFAULT_ACCOUNT: &str = "default";
+
+/// Named accounts for one platform, one of which is the default
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+#[serde(from = "StoredAccounts<T>")]
+#[serde(bound(deserialize = "T: serde::Deserialize<'de>"))]
+pub struct Accounts<T> {
+    default: Option<String>,
+    accounts: std::collections::BTreeMap<String, T>,
+}
+
+/// On-disk forms of [`Accounts`], including the single account per
+/// platform stored before named accounts existed
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum StoredAccounts<T> {
+    Named {
+        default: Option<String>,
+        accounts: std::collections::BTreeMap<String, T>,
+    },
+    Single(Option<T>),
+}
+
+impl<T> From<StoredAccounts<T>> for Accounts<T> {
+    fn from(stored: StoredAccounts<T>) -> Self {
+        match stored {
+            StoredAccounts::Named { default, accounts } => Self { default, accounts },
+            StoredAccounts::Single(account) => {
+                let mut accounts = Self::default();
+                if let Some(account) = account {
+                    accounts.insert(DEFAULT_ACCOUNT.to_string(), account);
+                }
+                accounts
+            }
+        }
+    }
+}
+
+impl<T> Default for Accounts<T> {
+    fn default() -> Self {
+        Self {
+            default: None,
+            accounts: std::collections::BTreeMap::new(),
+        }
+    }
+}
+
+impl<T> Accounts<T> {
+    /// Resolve an account name: the given one, else the default account,
+    /// else the only account if there is exactly one.
+    pub fn resolve<'a>(&'a self, name: Option<&'a str>) -> Option<&'a str> {
+        match name {
+            Some(name) => Some(name),
+            None => self.default.as_deref().or_else(|| {
+                let mut names = self.accounts.keys();
+                match (names.next(), names.next()) {
+                    (Some(only), None) => Some(only.as_str()),
+                    _ => None,
+                }
+            }),
+        }
+    }
+
+    /// Look up an account by name, or the default account.
+    pub fn get(&self, name: Option<&str>) -> Option<&T> {
+        self.accounts.get(self.resolve(name)?)
+    }
+
+    /// Like [`Accounts::get`], but with an error explaining how to add the
+    /// missing account.
+    pub fn require(&self, name: Option<&str>, platform: &dyn super::Platform) -> eyre::Result<&T> {
+        if let Some(account) = self.get(name) {
+            return Ok(account);
+        }
+        let (display, cmd) = (platform.display_name(), platform.name());
+        Err(match name {
+            Some(name) => eyre::eyre!(
+                "no {display} account named `{name}` — run `post {cmd} auth --account {name}` first"
+            ),
+            None if !self.is_empty() => eyre::eyre!(
+                "no default {display} account — pass --account or run `post {cmd} accounts default <name>`"
+            ),
+            None => eyre::eyre!("not authenticated with {display} — run `post {cmd} auth` first"),
+        })
+    }
+
+    /// Look up an account by name, or the default account.
+    pub fn get_mut(&mut self, name: Option<&str>) -> Option<&mut T> {
+        let name = self.resolve(name)?.to_string();
+        self.accounts.get_mut(&name)
+    }
+
+    /// Add or replace an account. The first account added becomes the default.
+    pub fn insert(&mut self, name: String, account: T) {
+        if self.default.is_none() && self.accounts.is_empty() {
+            self.default = Some(name.clone());
+        }
+        self.accounts.insert(name, account);
+    }
+
+    /// Remove an account by name, or the default account.
+    pub fn remove(&mut self, name: Option<&str>) -> Option<T> {
+        let name = self.resolve(name)?.to_string();
+        if self.default.as_deref() == Some(&name) {
+            self.default = None;
+        }
+        self.accounts.remove(&name)
+    }
+
+    pub fn rename(&mut self, from: &str, to: &str) -> eyre::Result<()> {
+        check_account_name(to)?;
+        if self.accounts.contains_key(to) {
+            eyre::bail!("account `{to}` already exists");
+        }
+        let account = self
+            .accounts
+            .remove(from)
+            .ok_or_else(|| eyre::eyre!("no account named `{from}`"))?;
+        self.accounts.insert(to.to_string(), account);
+        if self.default.as_deref() == Some(from) {
+            self.default = Some(to.to_string());
+        }
+        Ok(())
+    }
+
+    pub fn set_default(&mut self, name: &str) -> eyre::Result<()> {
+        if !self.accounts.contains_key(name) {
+            eyre::bail!("no account named `{name}`");
+        }
+        self.default = Some(name.to_string());
+        Ok(())
+    }
+
+    /// Name of the default account, if one is set
+    pub fn default_name(&self) -> Option<&str> {
+        self.default.as_deref()
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.accounts.keys().map(String::as_str)
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&str, &mut T)> {
+        self.accounts
+            .iter_mut()
+            .map(|(name, account)| (name.as_str(), account))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+}
+
+/// Check that an account name can be used. Names are part of the keys
+/// secrets are stored under (`x.<name>.access_token`), so they can't contain `.`.
+pub fn check_account_name(name: &str) -> eyre::Result<()> {
+    if name.trim().is_empty() {
+        eyre::bail!("account names can't be empty");
+    }
+    if name.contains('.') {
+        eyre::bail!("account name `{name}` can't contain `.`");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Accounts, DEFAULT_ACCOUNT, check_account_name};
+
+    #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq)]
+    struct Account {
+        client_id: String,
+    }
+
+    fn account(client_id: &str) -> Account {
+        Account {
+            client_id: client_id.to_string(),
+        }
+    }
+
+    #[test]
+    fn migrates_single_account() {
+        let accounts: Accounts<Account> =
+            serde_json::from_str(r#"{ "client_id": "legacy" }"#).unwrap();
+        assert_eq!(accounts.names().collect::<Vec<_>>(), [DEFAULT_ACCOUNT]);
+        assert_eq!(accounts.default_name(), Some(DEFAULT_ACCOUNT));
+        assert_eq!(accounts.get(None), Some(&account("legacy")));
+
+        let accounts: Accounts<Account> = serde_json::from_str("null").unwrap();
+        assert!(accounts.is_empty());
+    }
+
+    #[test]
+    fn round_trips_named_accounts() {
+        let mut accounts = Accounts::default();
+        accounts.insert("personal".to_string(), account("a"));
+        accounts.insert("work".to_string(), account("b"));
+        accounts.set_default("work").unwrap();
+
+        let json = serde_json::to_string(&accounts).unwrap();
+        let accounts: Accounts<Account> = serde_json::from_str(&json).unwrap();
+        assert_eq!(accounts.default_name(), Some("work"));
+        assert_eq!(accounts.get(None), Some(&account("b")));
+        assert_eq!(accounts.get(Some("personal")), Some(&account("a")));
+    }
+
+    #[test]
+    fn resolves_the_only_account_without_a_default() {
+        let accounts: Accounts<Account> = serde_json::from_str(
+            r#"{ "default": null, "accounts": { "solo": { "client_id": "a" } } }"#,
+        )
+        .unwrap();
+        assert_eq!(accounts.resolve(None), Some("solo"));
+        assert_eq!(accounts.resolve(Some("other")), Some("other"));
+    }
+
+    #[test]
+    fn checks_account_names() {
+        assert!(check_account_name("work").is_ok());
+        for name in ["", "  ", "work.alt"] {
+            assert!(check_account_name(name).is_err(), "{name:?}");
+        }
+    }
+}
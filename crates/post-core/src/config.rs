@@ -1,20 +1,34 @@
 use eyre::WrapErr as _;
 
+use super::Accounts;
+use super::secrets;
+
 const APP_NAME: &str = "post";
 
-/// Platform-agnostic configuration storage
-#[derive(serde::Serialize, serde::Deserialize, Default)]
+/// Platform-agnostic configuration storage.
+///
+/// Secrets are kept in memory as plain values; on disk they are replaced by
+/// references into the secret store.
+#[derive(serde::Serialize, serde::Deserialize, Default, Clone)]
 pub struct Config {
-    pub x: Option<XConfig>,
-    pub reddit: Option<RedditConfig>,
+    #[serde(default)]
+    pub x: Accounts<XConfig>,
+    #[serde(default)]
+    pub reddit: Accounts<RedditConfig>,
+    /// OAuth callback server settings, used unless overridden on the command line
+    #[serde(default, skip_serializing_if = "super::CallbackConfig::is_unset")]
+    pub callback: super::CallbackConfig,
+    /// Secrets as loaded, by store key, so `save` only writes changed ones
+    #[serde(skip)]
+    loaded: std::collections::HashMap<String, String>,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Clone)]
 pub struct XConfig {
     pub client_id: String,
     pub client_secret: String,
-    pub access_token: String,
-    pub refresh_token: Option<String>,
+    #[serde(flatten)]
+    pub tokens: Tokens,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Clone)]
@@ -22,37 +36,197 @@ pub struct RedditConfig {
     pub client_id: String,
     pub client_secret: String,
     pub username: String,
+    #[serde(flatten)]
+    pub tokens: Tokens,
+}
+
+/// OAuth tokens issued by a platform
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+pub struct Tokens {
     pub access_token: String,
     pub refresh_token: Option<String>,
+    /// When the access token expires, in seconds since the Unix epoch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<u64>,
 }
 
+impl Tokens {
+    /// Build tokens from a token response, where `expires_in` is the access
+    /// token lifetime in seconds.
+    pub fn new(
+        access_token: String,
+        refresh_token: Option<String>,
+        expires_in: Option<u64>,
+    ) -> Self {
+        Self {
+            access_token,
+            refresh_token,
+            expires_at: expires_in.map(|secs| unix_now() + secs),
+        }
+    }
+
+    /// Time left before the access token expires, if known.
+    /// Zero once it has expired.
+    pub fn remaining(&self) -> Option<std::time::Duration> {
+        self.expires_at
+            .map(|at| std::time::Duration::from_secs(at.saturating_sub(unix_now())))
+    }
+
+    /// Whether the access token expires within [`EXPIRY_MARGIN_SECS`], so it
+    /// should be refreshed before use
+    pub fn expires_soon(&self) -> bool {
+        self.remaining()
+            .is_some_and(|left| left.as_secs() < EXPIRY_MARGIN_SECS)
+    }
+
+    fn secrets_mut<'a>(&'a mut self, prefix: &str, secrets: &mut Secrets<'a>) {
+        secrets.push((format!("{prefix}.access_token"), &mut self.access_token));
+        if let Some(refresh_token) = &mut self.refresh_token {
+            secrets.push((format!("{prefix}.refresh_token"), refresh_token));
+        }
+    }
+
+    /// Human-readable token lifetime for status output, if known
+    pub fn lifetime(&self) -> Option<String> {
+        let left = self.remaining()?.as_secs();
+        if left == 0 {
+            return Some("token expired, refreshes on next use".to_string());
+        }
+        let (hours, minutes) = (left / 3600, left % 3600 / 60);
+        Some(if hours > 0 {
+            format!("token expires in {hours}h {minutes}m")
+        } else if minutes > 0 {
+            format!("token expires in {minutes}m")
+        } else {
+            format!("token expires in {left}s")
+        })
+    }
+}
+
+/// Refresh tokens this long before they expire to allow for clock skew
+const EXPIRY_MARGIN_SECS: u64 = 60;
+
+fn unix_now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |now| now.as_secs())
+}
+
+/// Secret fields paired with their key in the secret store
+type Secrets<'a> = Vec<(String, &'a mut String)>;
+
 impl Config {
     pub fn load() -> eyre::Result<Self> {
-        let path = Self::path()?;
-        if !path.exists() {
-            return Ok(Self::default());
+        let mut config = Self::read()?;
+        let store = Self::secret_store()?;
+
+        let mut plaintext = false;
+        let mut loaded = std::collections::HashMap::new();
+        for (key, value) in config.secrets_mut() {
+            match secrets::parse_reference(value) {
+                Some((backend, reference)) => {
+                    let secret = store.get(backend, reference)?;
+                    loaded.insert(key, secret.clone());
+                    *value = secret;
+                }
+                None => plaintext = true,
+            }
         }
-        let contents = std::fs::read_to_string(&path)
-            .wrap_err_with(|| format!("failed to read config from {}", path.display()))?;
-        serde_json::from_str(&contents)
-            .wrap_err_with(|| format!("failed to parse config from {}", path.display()))
+        config.loaded = loaded;
+
+        // Configs written before secrets moved to the store hold them in plaintext
+        if plaintext {
+            tracing::info!("moving plaintext secrets out of config.json");
+            config.save()?;
+        }
+
+        Ok(config)
     }
 
     pub fn save(&self) -> eyre::Result<()> {
         let path = Self::path()?;
+        let store = Self::secret_store()?;
+        let mut previous = Self::read()?;
+        let previous_references: std::collections::HashMap<_, _> = previous
+            .secrets_mut()
+            .into_iter()
+            .map(|(key, value)| (key, value.clone()))
+            .collect();
+
+        let mut stored = self.clone();
+        let mut references = std::collections::HashSet::new();
+        for (key, value) in stored.secrets_mut() {
+            let unchanged = self.loaded.get(&key).is_some_and(|loaded| loaded == value);
+            *value = match previous_references.get(&key) {
+                // Keep the stored secret rather than rewriting it
+                Some(reference) if unchanged && secrets::parse_reference(reference).is_some() => {
+                    reference.clone()
+                }
+                _ => store.put(&key, value)?,
+            };
+            references.insert(value.clone());
+        }
+
         if let Some(parent) = path.parent() {
             std::fs::create_dir_all(parent).wrap_err_with(|| {
                 format!("failed to create config directory {}", parent.display())
             })?;
         }
-        let contents = serde_json::to_string_pretty(self).wrap_err("failed to serialize config")?;
+        let contents =
+            serde_json::to_string_pretty(&stored).wrap_err("failed to serialize config")?;
         std::fs::write(&path, contents)
-            .wrap_err_with(|| format!("failed to write config to {}", path.display()))
+            .wrap_err_with(|| format!("failed to write config to {}", path.display()))?;
+
+        // Drop secrets no longer referenced, e.g. after logout
+        for (_, value) in previous.secrets_mut() {
+            if references.contains(value.as_str()) {
+                continue;
+            }
+            if let Some((backend, key)) = secrets::parse_reference(value) {
+                store.delete(backend, key)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Read `config.json` without resolving secret references.
+    fn read() -> eyre::Result<Self> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = std::fs::read_to_string(&path)
+            .wrap_err_with(|| format!("failed to read config from {}", path.display()))?;
+        serde_json::from_str(&contents)
+            .wrap_err_with(|| format!("failed to parse config from {}", path.display()))
+    }
+
+    fn secrets_mut(&mut self) -> Secrets<'_> {
+        let mut secrets = Vec::new();
+        for (name, x) in self.x.iter_mut() {
+            let prefix = format!("x.{name}");
+            secrets.push((format!("{prefix}.client_secret"), &mut x.client_secret));
+            x.tokens.secrets_mut(&prefix, &mut secrets);
+        }
+        for (name, reddit) in self.reddit.iter_mut() {
+            let prefix = format!("reddit.{name}");
+            secrets.push((format!("{prefix}.client_secret"), &mut reddit.client_secret));
+            reddit.tokens.secrets_mut(&prefix, &mut secrets);
+        }
+        secrets
+    }
+
+    fn secret_store() -> eyre::Result<secrets::SecretStore> {
+        let dirs = Self::dirs()?;
+        secrets::SecretStore::open(dirs.config_dir(), dirs.data_local_dir())
     }
 
     fn path() -> eyre::Result<std::path::PathBuf> {
-        let dirs = directories::ProjectDirs::from("", "", APP_NAME)
-            .ok_or_else(|| eyre::eyre!("could not determine config directory"))?;
-        Ok(dirs.config_dir().join("config.json"))
+        Ok(Self::dirs()?.config_dir().join("config.json"))
+    }
+
+    pub(crate) fn dirs() -> eyre::Result<directories::ProjectDirs> {
+        directories::ProjectDirs::from("", "", APP_NAME)
+            .ok_or_else(|| eyre::eyre!("could not determine config directory"))
     }
 }
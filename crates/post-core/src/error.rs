@@ -0,0 +1,69 @@
+/// Errors returned by platform API clients
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The access token is missing, expired or revoked
+    #[error("unauthorized: {0}")]
+    Unauthorized(String),
+
+    /// Too many requests; `reset` is when the limit lifts, if the platform said
+    #[error("rate limited{}", retry_hint(*reset))]
+    RateLimited {
+        reset: Option<std::time::SystemTime>,
+    },
+
+    /// The platform rejected the content itself
+    #[error("validation failed: {0}")]
+    Validation(String),
+
+    /// The post (or other resource) doesn't exist, e.g. it was already deleted
+    #[error("not found: {0}")]
+    NotFound(String),
+
+    /// The same content was already posted
+    #[error("duplicate content: {0}")]
+    Duplicate(String),
+
+    /// The request never got a response
+    #[error("network error")]
+    Network(#[source] Box<dyn std::error::Error + Send + Sync>),
+
+    /// The platform answered, but the response couldn't be parsed
+    #[error("unexpected response from the platform")]
+    Decode(#[source] Box<dyn std::error::Error + Send + Sync>),
+
+    /// Any other error reported by the platform
+    #[error("platform error ({code}): {message}")]
+    PlatformError { code: String, message: String },
+}
+
+impl Error {
+    pub fn network(error: impl std::error::Error + Send + Sync + 'static) -> Self {
+        Self::Network(Box::new(error))
+    }
+
+    pub fn decode(error: impl std::error::Error + Send + Sync + 'static) -> Self {
+        Self::Decode(Box::new(error))
+    }
+
+    /// Process exit code for this kind of error, so scripts can branch on it
+    pub const fn exit_code(&self) -> u8 {
+        match self {
+            Self::Unauthorized(_) => 3,
+            Self::RateLimited { .. } => 4,
+            Self::Validation(_) => 5,
+            Self::Duplicate(_) => 6,
+            Self::Network(_) => 7,
+            Self::PlatformError { .. } => 8,
+            Self::Decode(_) => 9,
+            Self::NotFound(_) => 10,
+        }
+    }
+}
+
+fn retry_hint(reset: Option<std::time::SystemTime>) -> String {
+    reset
+        .and_then(|reset| reset.duration_since(std::time::SystemTime::now()).ok())
+        .map_or_else(String::new, |wait| {
+            format!(" — try again in {}s", wait.as_secs() + 1)
+        })
+}
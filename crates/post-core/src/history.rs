@@ -0,0 +1,89 @@
+use eyre::WrapErr as _;
+
+/// Most posts remembered; older ones are dropped
+const MAX_ENTRIES: usize = 100;
+
+/// A post made by this tool, recorded so it can be undone
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
+pub struct HistoryEntry {
+    /// [`super::Platform::name`] of the platform posted to
+    pub platform: String,
+    /// Saved account the post was made with
+    pub account: Option<String>,
+    pub id: String,
+    pub url: String,
+    /// Unix timestamp of the post
+    pub posted_at: u64,
+}
+
+/// Recent posts, oldest first, stored in the local data directory
+#[derive(serde::Serialize, serde::Deserialize, Default)]
+#[serde(transparent)]
+pub struct History {
+    entries: Vec<HistoryEntry>,
+}
+
+impl History {
+    pub fn load() -> eyre::Result<Self> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let contents = std::fs::read_to_string(&path)
+            .wrap_err_with(|| format!("failed to read history from {}", path.display()))?;
+        serde_json::from_str(&contents)
+            .wrap_err_with(|| format!("failed to parse history from {}", path.display()))
+    }
+
+    pub fn save(&self) -> eyre::Result<()> {
+        let path = Self::path()?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).wrap_err_with(|| {
+                format!("failed to create data directory {}", parent.display())
+            })?;
+        }
+        let contents =
+            serde_json::to_string_pretty(self).wrap_err("failed to serialize history")?;
+        std::fs::write(&path, contents)
+            .wrap_err_with(|| format!("failed to write history to {}", path.display()))
+    }
+
+    /// Remember a post made through a platform.
+    pub fn record(platform: &dyn super::Platform, result: &super::PostResult) -> eyre::Result<()> {
+        let mut history = Self::load()?;
+        history.entries.push(HistoryEntry {
+            platform: platform.name().to_string(),
+            account: platform.account(),
+            id: result.id.clone(),
+            url: result.url.clone(),
+            posted_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs()),
+        });
+        let excess = history.entries.len().saturating_sub(MAX_ENTRIES);
+        history.entries.drain(..excess);
+        history.save()
+    }
+
+    /// Drop a post that was deleted, so it isn't undone again.
+    pub fn forget(platform: &str, id: &str) -> eyre::Result<()> {
+        let mut history = Self::load()?;
+        let before = history.entries.len();
+        history
+            .entries
+            .retain(|entry| !(entry.platform == platform && entry.id == id));
+        if history.entries.len() == before {
+            return Ok(());
+        }
+        history.save()
+    }
+
+    /// The most recent post.
+    pub fn last(&self) -> Option<&HistoryEntry> {
+        self.entries.last()
+    }
+
+    fn path() -> eyre::Result<std::path::PathBuf> {
+        Ok(super::Config::dirs()?.data_local_dir().join("history.json"))
+    }
+}
@@ -1,11 +1,27 @@
+mod accounts;
 mod config;
+mod error;
+mod history;
 mod oauth;
+mod platform;
+mod refresh;
+mod secrets;
 
-pub use config::{Config, RedditConfig, XConfig};
-pub use oauth::wait_for_callback;
+pub use accounts::{Accounts, DEFAULT_ACCOUNT, check_account_name};
+pub use config::{Config, RedditConfig, Tokens, XConfig};
+pub use error::Error;
+pub use history::{History, HistoryEntry};
+pub use oauth::{
+    AuthorizationCode, CallbackConfig, CallbackServer, TokenEndpoint, authorization_code,
+    read_pasted_callback, wait_for_callback,
+};
+pub use platform::{Capabilities, Content, Credentials, Platform};
+pub use refresh::with_refresh;
 
 /// Result of posting to a platform
 pub struct PostResult {
+    /// Platform ID of the posted content (tweet ID, Reddit fullname)
+    pub id: String,
     /// URL to the posted content
     pub url: String,
 }
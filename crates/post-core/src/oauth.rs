@@ -1,61 +1,560 @@
 use eyre::WrapErr as _;
 
 const TIMEOUT_SECS: u64 = 300;
+const DEFAULT_HOST: &str = "localhost";
+const DEFAULT_PORT: u16 = 8080;
+const DEFAULT_PATH: &str = "/callback";
 
+/// Where the local server listens for OAuth redirects. Unset fields fall
+/// back to `http://localhost:8080/callback`.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct CallbackConfig {
+    /// Host in the redirect URI, and the address to listen on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    /// Port to listen on; 0 picks a free port, which only works with
+    /// providers that accept any loopback port in the redirect URI
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    /// Path in the redirect URI
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+impl CallbackConfig {
+    /// Fill unset fields from `fallback`.
+    #[must_use]
+    pub fn or(self, fallback: &Self) -> Self {
+        Self {
+            host: self.host.or_else(|| fallback.host.clone()),
+            port: self.port.or(fallback.port),
+            path: self.path.or_else(|| fallback.path.clone()),
+        }
+    }
+
+    pub const fn is_unset(&self) -> bool {
+        self.host.is_none() && self.port.is_none() && self.path.is_none()
+    }
+
+    /// Redirect URI for this configuration, for flows that don't bind a
+    /// server. Fails for port 0, which is only known once bound.
+    pub fn redirect_uri(&self) -> eyre::Result<String> {
+        let port = self.port.unwrap_or(DEFAULT_PORT);
+        if port == 0 {
+            eyre::bail!("callback port 0 needs a local server; pick a fixed --callback-port");
+        }
+        Ok(self.redirect_uri_for(port))
+    }
+
+    fn redirect_uri_for(&self, port: u16) -> String {
+        let host = self.host.as_deref().unwrap_or(DEFAULT_HOST);
+        format!("http://{host}:{port}{}", self.path())
+    }
+
+    fn path(&self) -> String {
+        let path = self.path.as_deref().unwrap_or(DEFAULT_PATH);
+        if path.starts_with('/') {
+            path.to_string()
+        } else {
+            format!("/{path}")
+        }
+    }
+}
+
+/// Local server bound and ready to receive an OAuth redirect
+pub struct CallbackServer {
+    listener: tokio::net::TcpListener,
+    path: String,
+    redirect_uri: String,
+}
+
+impl CallbackServer {
+    /// Bind the callback server. With port 0 the OS picks a free port, and
+    /// [`CallbackServer::redirect_uri`] reflects it.
+    pub async fn bind(config: &CallbackConfig) -> eyre::Result<Self> {
+        let host = config.host.as_deref().unwrap_or(DEFAULT_HOST);
+        let port = config.port.unwrap_or(DEFAULT_PORT);
+        // `localhost` can resolve to IPv6 first; browsers reliably reach IPv4
+        let bind_host = if host == DEFAULT_HOST {
+            "127.0.0.1"
+        } else {
+            host
+        };
+
+        let addr = format!("{bind_host}:{port}");
+        let listener = tokio::net::TcpListener::bind(&addr).await.wrap_err_with(|| {
+            format!(
+                "failed to bind to {addr} — is another instance running? Pick another port with --callback-port"
+            )
+        })?;
+        let port = listener
+            .local_addr()
+            .wrap_err("failed to read the callback server address")?
+            .port();
+
+        Ok(Self {
+            redirect_uri: config.redirect_uri_for(port),
+            listener,
+            path: config.path(),
+        })
+    }
+
+    /// Redirect URI to register with the provider and send in the
+    /// authorization request
+    pub fn redirect_uri(&self) -> &str {
+        &self.redirect_uri
+    }
+}
+
+/// Query parameters of an OAuth redirect: `code` and `state` on success,
+/// `error` and `state` when the user denies access
 #[derive(serde::Deserialize)]
 struct CallbackQuery {
-    code: String,
-    state: String,
+    code: Option<String>,
+    state: Option<String>,
+    error: Option<String>,
+    error_description: Option<String>,
 }
 
-/// Starts a local HTTP server to receive OAuth callbacks.
+/// What an OAuth redirect means for the login
+enum Outcome {
+    Code(String),
+    /// The provider reported an error, e.g. the user clicked "Cancel"
+    Failed(String),
+    /// Not a redirect for this login; keep waiting
+    Ignored(&'static str),
+}
+
+impl CallbackQuery {
+    fn outcome(self, expected_state: &str) -> Outcome {
+        // Errors need our state too, so a stray request can't end the login
+        match self.state.as_deref() {
+            None => return Outcome::Ignored("The redirect is missing the `state` parameter."),
+            Some(state) if state != expected_state => {
+                return Outcome::Ignored("State mismatch! Authorization failed.");
+            }
+            Some(_) => {}
+        }
+        if let Some(error) = self.error {
+            let reason = match self.error_description {
+                Some(description) => format!("{error}: {description}"),
+                None if error == "access_denied" => "access was denied".to_string(),
+                None => error,
+            };
+            return Outcome::Failed(reason);
+        }
+        match self.code {
+            Some(code) => Outcome::Code(code),
+            None => Outcome::Ignored("The redirect is missing the authorization code."),
+        }
+    }
+}
+
+/// Serve OAuth callbacks on a bound server.
 /// Returns the authorization code once received.
-pub async fn wait_for_callback(expected_state: &str, port: u16) -> eyre::Result<String> {
-    let (tx, rx) = tokio::sync::oneshot::channel::<String>();
+pub async fn wait_for_callback(
+    server: CallbackServer,
+    expected_state: &str,
+) -> eyre::Result<String> {
+    let (tx, rx) = tokio::sync::oneshot::channel::<Result<String, String>>();
     let tx = std::sync::Arc::new(std::sync::Mutex::new(Some(tx)));
     let expected_state = expected_state.to_string();
 
     let app = axum::Router::new().route(
-        "/callback",
+        &server.path,
         axum::routing::get(handle_callback(tx, expected_state)),
     );
 
-    let addr = format!("127.0.0.1:{port}");
-    let listener = tokio::net::TcpListener::bind(&addr)
-        .await
-        .wrap_err_with(|| format!("failed to bind to {addr} — is another instance running?"))?;
-
+    let listener = server.listener;
     let server = tokio::spawn(async move {
         axum::serve(listener, app).await.ok();
     });
 
-    let code = tokio::time::timeout(std::time::Duration::from_secs(TIMEOUT_SECS), rx)
+    let result = tokio::time::timeout(std::time::Duration::from_secs(TIMEOUT_SECS), rx)
         .await
         .wrap_err("authorization timed out after 5 minutes")?
         .wrap_err("failed to receive authorization code")?;
 
     server.abort();
-    Ok(code)
+    result.map_err(|reason| {
+        super::Error::Unauthorized(format!("authorization failed — {reason}")).into()
+    })
+}
+
+/// Read the redirect URL (or just the authorization code) pasted on stdin,
+/// for logins where the browser can't reach the local callback server.
+/// Returns the authorization code.
+pub async fn read_pasted_callback(expected_state: &str) -> eyre::Result<String> {
+    use tokio::io::AsyncBufReadExt as _;
+
+    let mut line = String::new();
+    tokio::io::BufReader::new(tokio::io::stdin())
+        .read_line(&mut line)
+        .await
+        .wrap_err("failed to read from stdin")?;
+    parse_pasted_callback(line.trim(), expected_state)
+}
+
+/// Get the authorization code from a pasted redirect URL or bare code.
+fn parse_pasted_callback(input: &str, expected_state: &str) -> eyre::Result<String> {
+    if input.is_empty() {
+        eyre::bail!("no redirect URL or code pasted");
+    }
+
+    if !input.contains('?') {
+        // OAuth codes are URL-safe tokens; anything else is a mangled paste
+        if !input
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | '~'))
+        {
+            eyre::bail!("pasted text is neither a redirect URL nor an authorization code");
+        }
+        tracing::warn!("a bare code carries no state, so the redirect can't be verified");
+        return Ok(input.to_string());
+    }
+
+    let uri: axum::http::Uri = input.parse().wrap_err("pasted text is not a URL")?;
+    let axum::extract::Query(query) = axum::extract::Query::<CallbackQuery>::try_from_uri(&uri)
+        .wrap_err("pasted URL has no `code` and `state` parameters")?;
+    match query.outcome(expected_state) {
+        Outcome::Code(code) => Ok(code),
+        Outcome::Failed(reason) => {
+            Err(super::Error::Unauthorized(format!("authorization failed — {reason}")).into())
+        }
+        Outcome::Ignored(reason) => eyre::bail!("{reason}"),
+    }
+}
+
+/// An authorization code and the redirect URI it was issued for, which the
+/// token exchange has to repeat
+pub struct AuthorizationCode {
+    pub code: String,
+    pub redirect_uri: String,
+}
+
+/// Have the user authorize the app and return the authorization code.
+///
+/// `auth_url` builds the provider's authorization URL from the redirect URI
+/// and the state to send. The URL is opened in a browser and the redirect
+/// caught by a local server, or with `no_browser` printed so the redirect
+/// can be pasted back on stdin.
+pub async fn authorization_code(
+    callback: &CallbackConfig,
+    no_browser: bool,
+    auth_url: impl FnOnce(&str, &str) -> String,
+) -> eyre::Result<AuthorizationCode> {
+    use base64::Engine as _;
+    use rand::Rng as _;
+
+    let server = if no_browser {
+        None
+    } else {
+        Some(CallbackServer::bind(callback).await?)
+    };
+    let redirect_uri = match &server {
+        Some(server) => server.redirect_uri().to_string(),
+        None => callback.redirect_uri()?,
+    };
+
+    let state_bytes: [u8; 16] = rand::rng().random();
+    let state = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(state_bytes);
+    let auth_url = auth_url(&redirect_uri, &state);
+
+    let code = match server {
+        Some(server) => {
+            println!("Opening browser for authorization...");
+            println!("If the browser doesn't open, visit: {auth_url}");
+
+            let _ = open::that(&auth_url);
+
+            wait_for_callback(server, &state).await?
+        }
+        None => {
+            println!("Open this URL in any browser and authorize:\n\n{auth_url}\n");
+            println!(
+                "The browser then goes to {redirect_uri}, which may fail to load. \
+                 Paste the full URL from its address bar (or just the code):"
+            );
+            read_pasted_callback(&state).await?
+        }
+    };
+
+    Ok(AuthorizationCode { code, redirect_uri })
 }
 
+/// A provider's OAuth token endpoint, called with the app's client ID and
+/// secret as HTTP basic auth
+pub struct TokenEndpoint<'a> {
+    pub url: &'a str,
+    pub client_id: &'a str,
+    pub client_secret: &'a str,
+    /// Sent as the `User-Agent` header, for providers that require one
+    pub user_agent: Option<&'a str>,
+}
+
+#[derive(serde::Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_in: Option<u64>,
+}
+
+impl TokenEndpoint<'_> {
+    /// Exchange an authorization code for tokens. `extra` adds parameters
+    /// some providers need, e.g. a PKCE `code_verifier`.
+    pub async fn exchange_code(
+        &self,
+        code: &AuthorizationCode,
+        extra: &[(&str, &str)],
+    ) -> eyre::Result<super::Tokens> {
+        let mut params = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code.code.as_str()),
+            ("redirect_uri", code.redirect_uri.as_str()),
+        ];
+        params.extend_from_slice(extra);
+        self.request(&params)
+            .await
+            .wrap_err("failed to exchange the authorization code")
+    }
+
+    /// Get a new access token with a refresh token.
+    pub async fn refresh(&self, refresh_token: &str) -> eyre::Result<super::Tokens> {
+        let params = [
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+        ];
+        self.request(&params)
+            .await
+            .wrap_err("failed to refresh the access token")
+    }
+
+    async fn request(&self, params: &[(&str, &str)]) -> eyre::Result<super::Tokens> {
+        let mut request = reqwest::Client::new()
+            .post(self.url)
+            .basic_auth(self.client_id, Some(self.client_secret))
+            .form(params);
+        if let Some(user_agent) = self.user_agent {
+            request = request.header("User-Agent", user_agent);
+        }
+        let response = request.send().await.map_err(super::Error::network)?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            eyre::bail!("token request failed ({status}): {body}");
+        }
+
+        let token: TokenResponse = response.json().await.map_err(super::Error::decode)?;
+        Ok(super::Tokens::new(
+            token.access_token,
+            token.refresh_token,
+            token.expires_in,
+        ))
+    }
+}
+
+/// Sends the authorization code, or why authorization failed, once
+type CallbackSender =
+    std::sync::Arc<std::sync::Mutex<Option<tokio::sync::oneshot::Sender<Result<String, String>>>>>;
+
+/// Status and HTML page shown in the browser after the redirect
+type CallbackPage = (axum::http::StatusCode, axum::response::Html<String>);
+
 fn handle_callback(
-    tx: std::sync::Arc<std::sync::Mutex<Option<tokio::sync::oneshot::Sender<String>>>>,
+    tx: CallbackSender,
     expected_state: String,
 ) -> impl Fn(
     axum::extract::Query<CallbackQuery>,
-) -> std::pin::Pin<Box<dyn std::future::Future<Output = String> + Send>>
+) -> std::pin::Pin<Box<dyn std::future::Future<Output = CallbackPage> + Send>>
        + Clone {
-    move |query: axum::extract::Query<CallbackQuery>| {
+    move |axum::extract::Query(query): axum::extract::Query<CallbackQuery>| {
         let tx = std::sync::Arc::clone(&tx);
         let expected_state = expected_state.clone();
         Box::pin(async move {
-            if query.state != expected_state {
-                return "State mismatch! Authorization failed.".to_string();
-            }
+            let (result, response) = match query.outcome(&expected_state) {
+                Outcome::Code(code) => (
+                    Ok(code),
+                    page(
+                        axum::http::StatusCode::OK,
+                        "Authorization successful",
+                        "You can close this window and return to the terminal.",
+                    ),
+                ),
+                Outcome::Failed(reason) => {
+                    let response = page(
+                        axum::http::StatusCode::FORBIDDEN,
+                        "Authorization failed",
+                        &reason,
+                    );
+                    (Err(reason), response)
+                }
+                Outcome::Ignored(reason) => {
+                    return page(
+                        axum::http::StatusCode::BAD_REQUEST,
+                        "Authorization failed",
+                        reason,
+                    );
+                }
+            };
             if let Some(sender) = tx.lock().ok().and_then(|mut guard| guard.take()) {
-                let _ = sender.send(query.code.clone());
+                let _ = sender.send(result);
             }
-            "Authorization successful! You can close this window.".to_string()
+            response
         })
     }
 }
+
+/// A small styled page for the browser tab the redirect lands in
+fn page(status: axum::http::StatusCode, title: &str, message: &str) -> CallbackPage {
+    let accent = if status.is_success() {
+        "#16a34a"
+    } else {
+        "#dc2626"
+    };
+    let (title, message) = (escape_html(title), escape_html(message));
+    let html = format!(
+        r#"<!doctype html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>{title} — post</title>
+<style>
+  body {{ margin: 0; min-height: 100vh; display: flex; align-items: center; justify-content: center;
+         font-family: system-ui, -apple-system, sans-serif; background: #f4f4f5; color: #18181b; }}
+  main {{ background: #fff; padding: 2.5rem 3rem; border-radius: 12px; max-width: 28rem;
+          box-shadow: 0 4px 24px rgb(0 0 0 / 0.08); border-top: 6px solid {accent}; }}
+  h1 {{ margin: 0 0 0.75rem; font-size: 1.4rem; color: {accent}; }}
+  p {{ margin: 0; line-height: 1.5; }}
+  @media (prefers-color-scheme: dark) {{
+    body {{ background: #18181b; color: #f4f4f5; }}
+    main {{ background: #27272a; }}
+  }}
+</style>
+</head>
+<body>
+<main>
+<h1>{title}</h1>
+<p>{message}</p>
+</main>
+</body>
+</html>
+"#
+    );
+    (status, axum::response::Html(html))
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CallbackQuery, Outcome, escape_html, parse_pasted_callback};
+
+    #[test]
+    fn parses_pasted_urls() {
+        let code =
+            parse_pasted_callback("http://localhost:8080/callback?state=abc&code=xyz", "abc")
+                .unwrap();
+        assert_eq!(code, "xyz");
+
+        assert!(
+            parse_pasted_callback("http://localhost:8080/callback?state=other&code=xyz", "abc")
+                .is_err()
+        );
+        assert!(parse_pasted_callback("http://localhost:8080/callback?code=xyz", "abc").is_err());
+    }
+
+    #[test]
+    fn reports_pasted_errors() {
+        let error = parse_pasted_callback(
+            "http://localhost:8080/callback?state=abc&error=access_denied",
+            "abc",
+        )
+        .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<crate::Error>(),
+            Some(crate::Error::Unauthorized(_))
+        ));
+    }
+
+    #[test]
+    fn accepts_only_plain_bare_codes() {
+        assert_eq!(
+            parse_pasted_callback("a-b_c.d~1", "abc").unwrap(),
+            "a-b_c.d~1"
+        );
+        for input in ["", "two words", "code=xyz", "localhost:8080/callback#x"] {
+            assert!(parse_pasted_callback(input, "abc").is_err(), "{input}");
+        }
+    }
+
+    fn query(code: Option<&str>, state: Option<&str>, error: Option<&str>) -> CallbackQuery {
+        CallbackQuery {
+            code: code.map(str::to_string),
+            state: state.map(str::to_string),
+            error: error.map(str::to_string),
+            error_description: None,
+        }
+    }
+
+    #[test]
+    fn accepts_codes_with_matching_state() {
+        assert!(matches!(
+            query(Some("xyz"), Some("abc"), None).outcome("abc"),
+            Outcome::Code(code) if code == "xyz"
+        ));
+        for query in [
+            query(Some("xyz"), Some("other"), None),
+            query(Some("xyz"), None, None),
+            query(None, Some("abc"), None),
+        ] {
+            assert!(matches!(query.outcome("abc"), Outcome::Ignored(_)));
+        }
+    }
+
+    #[test]
+    fn fails_only_on_errors_with_matching_state() {
+        assert!(matches!(
+            query(None, Some("abc"), Some("access_denied")).outcome("abc"),
+            Outcome::Failed(reason) if reason == "access was denied"
+        ));
+        let described = CallbackQuery {
+            error_description: Some("bad scope".to_string()),
+            ..query(None, Some("abc"), Some("invalid_scope"))
+        };
+        assert!(matches!(
+            described.outcome("abc"),
+            Outcome::Failed(reason) if reason == "invalid_scope: bad scope"
+        ));
+        for query in [
+            query(None, None, Some("access_denied")),
+            query(None, Some("other"), Some("access_denied")),
+        ] {
+            assert!(matches!(query.outcome("abc"), Outcome::Ignored(_)));
+        }
+    }
+
+    #[test]
+    fn escapes_html() {
+        assert_eq!(
+            escape_html(r#"<a href="x">Tom & Jerry's</a>"#),
+            "&lt;a href=&quot;x&quot;&gt;Tom &amp; Jerry&#39;s&lt;/a&gt;"
+        );
+        assert_eq!(escape_html("plain text"), "plain text");
+    }
+}
@@ -0,0 +1,171 @@
+/// A social network that content can be published to.
+///
+/// Implemented by each platform crate so the CLI (and other tooling) can
+/// drive every network through the same interface.
+#[async_trait::async_trait]
+pub trait Platform: Send + Sync {
+    /// Short identifier used on the command line, e.g. `x` or `reddit`
+    fn name(&self) -> &'static str;
+
+    /// Human-readable platform name, e.g. `X` or `Reddit`
+    fn display_name(&self) -> &'static str;
+
+    /// What kinds of content this platform accepts
+    fn capabilities(&self) -> Capabilities;
+
+    /// Run the platform's login flow and persist the resulting credentials.
+    async fn authenticate(&self, credentials: &Credentials) -> eyre::Result<()>;
+
+    /// Publish content. Handles token refresh automatically.
+    async fn publish(&self, content: &Content) -> eyre::Result<super::PostResult>;
+
+    /// Delete a post, given the ID from its [`super::PostResult`].
+    async fn delete(&self, id: &str) -> eyre::Result<()>;
+
+    /// Name of the saved account posts are made with, if one is set up
+    fn account(&self) -> Option<String>;
+
+    /// Describe the current auth state, or `None` if not authenticated.
+    /// Fails if the saved credentials can't be read, e.g. the keyring is locked.
+    fn status(&self) -> eyre::Result<Option<String>>;
+
+    /// Clear saved credentials
+    fn logout(&self) -> eyre::Result<()>;
+}
+
+/// What a platform supports, so callers can check content before publishing
+#[derive(Clone, Copy, Debug)]
+pub struct Capabilities {
+    /// Maximum length of [`Content::text`] in characters, if limited
+    pub max_text_len: Option<usize>,
+    /// Accepts a body separate from the main text
+    pub body: bool,
+    /// Accepts a link
+    pub link: bool,
+    /// If set, the link is appended to the text after a blank line, and it and
+    /// any URL in the text count as this many characters (X shortens every
+    /// link to a 23-character t.co URL)
+    pub link_len: Option<usize>,
+    /// Requires [`Content::community`] to be set
+    pub community: bool,
+}
+
+impl Capabilities {
+    /// Check that content can be published without contacting the platform.
+    pub fn check(&self, content: &Content) -> eyre::Result<()> {
+        if let Some(max) = self.max_text_len {
+            let mut len = self.text_len(&content.text);
+            if let (Some(_), Some(link_len)) = (&content.link, self.link_len) {
+                len += 2 + link_len;
+            }
+            if len > max {
+                eyre::bail!("text is {len} characters, the limit is {max}");
+            }
+        }
+        if self.community && content.community.is_none() {
+            eyre::bail!("a community (e.g. subreddit) is required");
+        }
+        if !self.link && content.link.is_some() {
+            eyre::bail!("links are not supported");
+        }
+        Ok(())
+    }
+
+    /// Length of `text` as the platform counts it against
+    /// [`Capabilities::max_text_len`]
+    pub fn text_len(&self, text: &str) -> usize {
+        let len = text.chars().count();
+        let Some(link_len) = self.link_len else {
+            return len;
+        };
+        text.split(char::is_whitespace)
+            .filter(|word| word.starts_with("https://") || word.starts_with("http://"))
+            .fold(len, |len, url| len - url.chars().count() + link_len)
+    }
+}
+
+/// Login details for [`Platform::authenticate`].
+/// Which optional fields are required depends on the platform.
+#[derive(Clone, Default)]
+pub struct Credentials {
+    pub client_id: String,
+    pub client_secret: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Two-factor code to log in with the password; used once, never saved
+    pub otp: Option<String>,
+    /// Overrides for the OAuth callback server, for browser-based flows
+    pub callback: super::CallbackConfig,
+    /// Authorize in a browser rather than with [`Credentials::password`], on
+    /// platforms that support both
+    pub browser: bool,
+    /// Print the authorization URL and read the redirect from stdin instead
+    /// of opening a browser, for headless and remote sessions
+    pub no_browser: bool,
+    /// OAuth scopes to request; the platform's defaults if empty
+    pub scopes: Vec<String>,
+}
+
+/// Platform-agnostic content to publish
+#[derive(Clone, Default)]
+pub struct Content {
+    /// Main text: the tweet on X, the title on Reddit
+    pub text: String,
+    /// Longer body text; ignored by platforms without [`Capabilities::body`]
+    pub body: Option<String>,
+    /// URL to share
+    pub link: Option<String>,
+    /// Where to post within the platform, e.g. a subreddit
+    pub community: Option<String>,
+    /// Skip the platform's own checks before posting, e.g. subreddit requirements
+    pub skip_checks: bool,
+    /// Post even if the platform finds the link was already posted there
+    pub force: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Capabilities, Content};
+
+    const X: Capabilities = Capabilities {
+        max_text_len: Some(280),
+        body: false,
+        link: true,
+        link_len: Some(23),
+        community: false,
+    };
+
+    #[test]
+    fn counts_urls_as_link_len() {
+        let url = format!("https://example.com/{}", "a".repeat(300));
+        assert_eq!(X.text_len("hello"), 5);
+        assert_eq!(X.text_len(&format!("see {url} now")), 4 + 23 + 4);
+        assert_eq!(X.text_len("http://a.b"), 23);
+        assert_eq!(X.text_len("no https: or http:/ here"), 24);
+
+        let uncounted = Capabilities {
+            link_len: None,
+            ..X
+        };
+        assert_eq!(uncounted.text_len(&url), url.len());
+    }
+
+    #[test]
+    fn checks_text_with_link() {
+        let content = |text: &str, link: Option<&str>| Content {
+            text: text.to_string(),
+            link: link.map(str::to_string),
+            ..Default::default()
+        };
+        assert!(
+            X.check(&content(&"a".repeat(255), Some("https://example.com")))
+                .is_ok()
+        );
+        assert!(
+            X.check(&content(&"a".repeat(256), Some("https://example.com")))
+                .is_err()
+        );
+        let long_url = format!("https://example.com/{}", "a".repeat(300));
+        assert!(X.check(&content(&long_url, None)).is_ok());
+    }
+}
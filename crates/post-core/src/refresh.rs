@@ -0,0 +1,92 @@
+use eyre::WrapErr as _;
+
+/// Serializes the load-modify-save of refreshed tokens within this process
+static SAVE_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+/// Run an authenticated API call, refreshing the access token first if it is
+/// about to expire, or refreshing and retrying once if the platform rejects it
+/// as unauthorized.
+///
+/// `tokens` selects the platform's tokens within `config`. Refreshed tokens
+/// are written into a freshly loaded config, which is saved before the call
+/// is made, so concurrent refreshes of other platforms aren't overwritten.
+pub async fn with_refresh<T, Select, Op, OpFut, Refresh, RefreshFut>(
+    config: &mut super::Config,
+    tokens: Select,
+    refresh: Refresh,
+    op: Op,
+) -> eyre::Result<T>
+where
+    Select: Fn(&mut super::Config) -> Option<&mut super::Tokens>,
+    Op: Fn(String) -> OpFut,
+    OpFut: Future<Output = Result<T, super::Error>>,
+    Refresh: FnOnce(String) -> RefreshFut,
+    RefreshFut: Future<Output = eyre::Result<super::Tokens>>,
+{
+    let current = tokens(config)
+        .cloned()
+        .ok_or_else(|| eyre::eyre!("not authenticated"))?;
+
+    if current.expires_soon() {
+        if let Some(refresh_token) = current.refresh_token {
+            tracing::debug!("access token expires soon, refreshing ahead of time");
+            let access_token = refresh_tokens(config, &tokens, refresh, refresh_token).await?;
+            return Ok(op(access_token).await?);
+        }
+    }
+
+    let reason = match op(current.access_token).await {
+        Err(super::Error::Unauthorized(reason)) => reason,
+        result => return Ok(result?),
+    };
+
+    let Some(refresh_token) = current.refresh_token else {
+        return Err(super::Error::Unauthorized(reason).into());
+    };
+
+    tracing::debug!("access token rejected, attempting token refresh");
+    let access_token = refresh_tokens(config, &tokens, refresh, refresh_token)
+        .await
+        .wrap_err_with(|| format!("access token rejected: {reason}"))?;
+
+    Ok(op(access_token).await?)
+}
+
+/// Exchange the refresh token for new tokens and persist them.
+/// Returns the new access token.
+async fn refresh_tokens<Select, Refresh, RefreshFut>(
+    config: &mut super::Config,
+    tokens: &Select,
+    refresh: Refresh,
+    refresh_token: String,
+) -> eyre::Result<String>
+where
+    Select: Fn(&mut super::Config) -> Option<&mut super::Tokens>,
+    Refresh: FnOnce(String) -> RefreshFut,
+    RefreshFut: Future<Output = eyre::Result<super::Tokens>>,
+{
+    let mut fresh = refresh(refresh_token.clone())
+        .await
+        .map_err(|e| super::Error::Unauthorized(format!("token refresh failed: {e:#}")))?;
+    // Some platforms only issue a refresh token once; keep using the old one
+    if fresh.refresh_token.is_none() {
+        fresh.refresh_token = Some(refresh_token);
+    }
+
+    let access_token = fresh.access_token.clone();
+    {
+        let _guard = SAVE_LOCK
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        // Another platform may have saved since `config` was loaded (e.g. in
+        // `post all`); only replace this platform's tokens
+        let mut latest = super::Config::load().wrap_err("failed to save refreshed tokens")?;
+        if let Some(slot) = tokens(&mut latest) {
+            *slot = fresh;
+        }
+        latest.save().wrap_err("failed to save refreshed tokens")?;
+        *config = latest;
+    }
+
+    Ok(access_token)
+}
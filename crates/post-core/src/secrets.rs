@@ -0,0 +1,211 @@
+//! Secret storage for client secrets and tokens.
+//!
+//! `config.json` only holds references like `keyring:x.access_token`; the
+//! values live in the OS credential store, or in an encrypted file when no
+//! credential store is available.
+
+use chacha20poly1305::AeadCore as _;
+use chacha20poly1305::aead::{Aead as _, KeyInit as _, OsRng};
+use eyre::WrapErr as _;
+
+const SERVICE: &str = "post";
+const BACKEND_ENV: &str = "POST_SECRET_BACKEND";
+const NONCE_LEN: usize = 12;
+
+/// Where secrets are stored
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// Secret Service / keyutils on Linux, Keychain on macOS, Credential Manager on Windows
+    Keyring,
+    /// ChaCha20-Poly1305 encrypted file, keyed by a file in the data directory.
+    /// The key sits on the same disk, so this only obfuscates: anyone who can
+    /// read both files can decrypt the secrets.
+    EncryptedFile,
+}
+
+impl Backend {
+    const fn prefix(self) -> &'static str {
+        match self {
+            Self::Keyring => "keyring",
+            Self::EncryptedFile => "encrypted-file",
+        }
+    }
+
+    fn from_prefix(prefix: &str) -> Option<Self> {
+        match prefix {
+            "keyring" => Some(Self::Keyring),
+            "encrypted-file" => Some(Self::EncryptedFile),
+            _ => None,
+        }
+    }
+}
+
+/// Parse a reference stored in `config.json`.
+/// Returns `None` for plaintext values written before secrets were moved out.
+pub fn parse_reference(value: &str) -> Option<(Backend, &str)> {
+    let (prefix, key) = value.split_once(':')?;
+    Some((Backend::from_prefix(prefix)?, key))
+}
+
+pub struct SecretStore {
+    preferred: Backend,
+    file: EncryptedFile,
+}
+
+impl SecretStore {
+    /// Open the store, preferring the backend named by `POST_SECRET_BACKEND`
+    /// (`keyring` or `encrypted-file`), or the OS keyring by default.
+    pub fn open(config_dir: &std::path::Path, data_dir: &std::path::Path) -> eyre::Result<Self> {
+        let preferred = match std::env::var(BACKEND_ENV) {
+            Ok(name) => Backend::from_prefix(&name)
+                .ok_or_else(|| eyre::eyre!("unknown {BACKEND_ENV} `{name}`"))?,
+            Err(_) => Backend::Keyring,
+        };
+        Ok(Self {
+            preferred,
+            file: EncryptedFile {
+                path: config_dir.join("secrets.enc"),
+                key_path: data_dir.join("secrets.key"),
+            },
+        })
+    }
+
+    /// Store a secret, returning the reference to keep in its place.
+    /// Falls back to the encrypted file if the keyring is unavailable.
+    pub fn put(&self, key: &str, value: &str) -> eyre::Result<String> {
+        let backend = match self.preferred {
+            Backend::Keyring => match keyring_entry(key).and_then(|e| Ok(e.set_password(value)?)) {
+                Ok(()) => Backend::Keyring,
+                Err(e) => {
+                    tracing::warn!("OS keyring unavailable, using encrypted file: {e:#}");
+                    self.file.put(key, value)?;
+                    Backend::EncryptedFile
+                }
+            },
+            Backend::EncryptedFile => {
+                self.file.put(key, value)?;
+                Backend::EncryptedFile
+            }
+        };
+        Ok(format!("{}:{key}", backend.prefix()))
+    }
+
+    /// Look up the secret behind a reference.
+    pub fn get(&self, backend: Backend, key: &str) -> eyre::Result<String> {
+        match backend {
+            Backend::Keyring => keyring_entry(key)?
+                .get_password()
+                .wrap_err_with(|| format!("failed to read `{key}` from the OS keyring")),
+            Backend::EncryptedFile => self
+                .file
+                .get(key)?
+                .ok_or_else(|| eyre::eyre!("secret `{key}` missing from encrypted file")),
+        }
+    }
+
+    /// Remove the secret behind a reference. Missing secrets are not an error.
+    pub fn delete(&self, backend: Backend, key: &str) -> eyre::Result<()> {
+        match backend {
+            Backend::Keyring => match keyring_entry(key)?.delete_credential() {
+                Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+                Err(e) => Err(e).wrap_err_with(|| format!("failed to delete `{key}`")),
+            },
+            Backend::EncryptedFile => self.file.delete(key),
+        }
+    }
+}
+
+fn keyring_entry(key: &str) -> eyre::Result<keyring::Entry> {
+    keyring::Entry::new(SERVICE, key).wrap_err("failed to open OS keyring entry")
+}
+
+/// Secrets map encrypted with ChaCha20-Poly1305, stored as nonce || ciphertext
+struct EncryptedFile {
+    path: std::path::PathBuf,
+    key_path: std::path::PathBuf,
+}
+
+type Secrets = std::collections::BTreeMap<String, String>;
+
+impl EncryptedFile {
+    fn get(&self, key: &str) -> eyre::Result<Option<String>> {
+        Ok(self.read()?.remove(key))
+    }
+
+    fn put(&self, key: &str, value: &str) -> eyre::Result<()> {
+        let mut secrets = self.read()?;
+        secrets.insert(key.to_string(), value.to_string());
+        self.write(&secrets)
+    }
+
+    fn delete(&self, key: &str) -> eyre::Result<()> {
+        let mut secrets = self.read()?;
+        if secrets.remove(key).is_some() {
+            self.write(&secrets)?;
+        }
+        Ok(())
+    }
+
+    fn read(&self) -> eyre::Result<Secrets> {
+        if !self.path.exists() {
+            return Ok(Secrets::new());
+        }
+        let contents = std::fs::read(&self.path)
+            .wrap_err_with(|| format!("failed to read secrets from {}", self.path.display()))?;
+        if contents.len() < NONCE_LEN {
+            eyre::bail!("secrets file {} is truncated", self.path.display());
+        }
+        let (nonce, ciphertext) = contents.split_at(NONCE_LEN);
+        let plaintext = self
+            .cipher()?
+            .decrypt(nonce.into(), ciphertext)
+            .map_err(|_| eyre::eyre!("failed to decrypt {}", self.path.display()))?;
+        serde_json::from_slice(&plaintext).wrap_err("failed to parse decrypted secrets")
+    }
+
+    fn write(&self, secrets: &Secrets) -> eyre::Result<()> {
+        let plaintext = serde_json::to_vec(secrets).wrap_err("failed to serialize secrets")?;
+        let nonce = chacha20poly1305::ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher()?
+            .encrypt(&nonce, plaintext.as_slice())
+            .map_err(|_| eyre::eyre!("failed to encrypt secrets"))?;
+
+        let mut contents = nonce.to_vec();
+        contents.extend(ciphertext);
+        write_private(&self.path, &contents)
+            .wrap_err_with(|| format!("failed to write secrets to {}", self.path.display()))
+    }
+
+    /// Cipher keyed by the key file, generating it on first use
+    fn cipher(&self) -> eyre::Result<chacha20poly1305::ChaCha20Poly1305> {
+        let key = if self.key_path.exists() {
+            std::fs::read(&self.key_path)
+                .wrap_err_with(|| format!("failed to read {}", self.key_path.display()))?
+        } else {
+            let key = chacha20poly1305::ChaCha20Poly1305::generate_key(&mut OsRng).to_vec();
+            write_private(&self.key_path, &key)
+                .wrap_err_with(|| format!("failed to write {}", self.key_path.display()))?;
+            key
+        };
+        chacha20poly1305::ChaCha20Poly1305::new_from_slice(&key)
+            .map_err(|_| eyre::eyre!("invalid key in {}", self.key_path.display()))
+    }
+}
+
+/// Write a file readable only by the current user.
+fn write_private(path: &std::path::Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::io::Write as _;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt as _;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(contents)
+}
@@ -1,21 +1,103 @@
 use eyre::WrapErr as _;
 
 const TOKEN_URL: &str = "https://www.reddit.com/api/v1/access_token";
+const AUTHORIZE_URL: &str = "https://www.reddit.com/api/v1/authorize";
+/// Scopes for everything `post` does, plus `identity` to look up the username
+pub const DEFAULT_SCOPES: &[&str] = &["identity", "submit", "edit", "read", "flair", "history"];
 
 #[derive(serde::Deserialize)]
 struct TokenResponse {
     access_token: String,
     refresh_token: Option<String>,
+    expires_in: Option<u64>,
+}
+
+impl From<TokenResponse> for post_core::Tokens {
+    fn from(token: TokenResponse) -> Self {
+        Self::new(token.access_token, token.refresh_token, token.expires_in)
+    }
 }
 
 /// Authenticate using Reddit's password grant flow (for "script" apps).
 /// This requires a Reddit app registered as "script" type.
+///
+/// Accounts with two-factor authentication need `otp`, the current code from
+/// the authenticator app. Without one, a rejected login on a terminal asks
+/// for a code and retries, since Reddit reports a missing code the same way
+/// as a wrong password.
 pub async fn authenticate(
     client_id: &str,
     client_secret: &str,
     username: &str,
     password: &str,
-) -> eyre::Result<(String, Option<String>)> {
+    otp: Option<&str>,
+) -> eyre::Result<post_core::Tokens> {
+    use std::io::IsTerminal as _;
+
+    if let Some(otp) = otp {
+        return password_grant(
+            client_id,
+            client_secret,
+            username,
+            &format!("{password}:{otp}"),
+        )
+        .await;
+    }
+
+    let result = password_grant(client_id, client_secret, username, password).await;
+    let rejected = result.as_ref().is_err_and(|error| {
+        matches!(
+            error.downcast_ref::<post_core::Error>(),
+            Some(post_core::Error::Unauthorized(_))
+        )
+    });
+    if !rejected || !std::io::stdin().is_terminal() {
+        return result;
+    }
+
+    let Some(otp) = prompt_otp().await? else {
+        return result;
+    };
+    password_grant(
+        client_id,
+        client_secret,
+        username,
+        &format!("{password}:{otp}"),
+    )
+    .await
+}
+
+/// Ask for a two-factor code, or `None` if the user enters nothing.
+async fn prompt_otp() -> eyre::Result<Option<String>> {
+    use tokio::io::AsyncBufReadExt as _;
+
+    println!(
+        "Reddit rejected the login. If the account uses two-factor authentication, \
+         enter the code from your authenticator app (or press Enter to give up):"
+    );
+    let mut line = String::new();
+    tokio::io::BufReader::new(tokio::io::stdin())
+        .read_line(&mut line)
+        .await
+        .wrap_err("failed to read from stdin")?;
+    let otp = line.trim();
+    Ok((!otp.is_empty()).then(|| otp.to_string()))
+}
+
+/// Reddit answers a rejected password grant with a 200 and an `error` field
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum PasswordGrantResponse {
+    Token(TokenResponse),
+    Error { error: String },
+}
+
+async fn password_grant(
+    client_id: &str,
+    client_secret: &str,
+    username: &str,
+    password: &str,
+) -> eyre::Result<post_core::Tokens> {
     use base64::Engine as _;
 
     let http = reqwest::Client::new();
@@ -44,12 +126,61 @@ pub async fn authenticate(
         eyre::bail!("Reddit authentication failed ({status}): {body}");
     }
 
-    let token: TokenResponse = response
+    let token: PasswordGrantResponse = response
         .json()
         .await
         .wrap_err("failed to parse Reddit token response")?;
 
-    Ok((token.access_token, token.refresh_token))
+    match token {
+        PasswordGrantResponse::Token(token) => Ok(token.into()),
+        PasswordGrantResponse::Error { error } if error == "invalid_grant" => {
+            Err(post_core::Error::Unauthorized(
+                "Reddit rejected the username, password or two-factor code".to_string(),
+            )
+            .into())
+        }
+        PasswordGrantResponse::Error { error } => {
+            eyre::bail!("Reddit authentication failed: {error}")
+        }
+    }
+}
+
+/// Authenticate using Reddit's authorization code flow (for "web app" and
+/// "installed app" types), asking for a permanent refresh token. Installed
+/// apps have no secret; pass an empty `client_secret`.
+pub async fn authorize(
+    client_id: &str,
+    client_secret: &str,
+    callback: &post_core::CallbackConfig,
+    no_browser: bool,
+    scopes: &[String],
+) -> eyre::Result<post_core::Tokens> {
+    if callback.port == Some(0) {
+        eyre::bail!(
+            "Reddit only accepts the exact redirect URI registered for the app; \
+             pick a fixed --callback-port instead of 0"
+        );
+    }
+
+    let scope = if scopes.is_empty() {
+        DEFAULT_SCOPES.join(" ")
+    } else {
+        scopes.join(" ")
+    };
+    let code = post_core::authorization_code(callback, no_browser, |redirect_uri, state| {
+        format!(
+            "{AUTHORIZE_URL}?client_id={}&response_type=code&state={}&redirect_uri={}&duration=permanent&scope={}",
+            urlencoding::encode(client_id),
+            urlencoding::encode(state),
+            urlencoding::encode(redirect_uri),
+            urlencoding::encode(&scope),
+        )
+    })
+    .await?;
+
+    token_endpoint(client_id, client_secret)
+        .exchange_code(&code, &[])
+        .await
 }
 
 /// Refresh an access token.
@@ -57,38 +188,17 @@ pub async fn refresh(
     client_id: &str,
     client_secret: &str,
     refresh_token: &str,
-) -> eyre::Result<(String, Option<String>)> {
-    use base64::Engine as _;
-
-    let http = reqwest::Client::new();
-
-    let credentials =
-        base64::engine::general_purpose::STANDARD.encode(format!("{client_id}:{client_secret}"));
-
-    let params = [
-        ("grant_type", "refresh_token"),
-        ("refresh_token", refresh_token),
-    ];
-
-    let response = http
-        .post(TOKEN_URL)
-        .header("Authorization", format!("Basic {credentials}"))
-        .header("User-Agent", super::USER_AGENT)
-        .form(&params)
-        .send()
+) -> eyre::Result<post_core::Tokens> {
+    token_endpoint(client_id, client_secret)
+        .refresh(refresh_token)
         .await
-        .wrap_err("failed to refresh Reddit token")?;
+}
 
-    if !response.status().is_success() {
-        let status = response.status();
-        let body = response.text().await.unwrap_or_default();
-        eyre::bail!("Reddit token refresh failed ({status}): {body}");
+fn token_endpoint<'a>(client_id: &'a str, client_secret: &'a str) -> post_core::TokenEndpoint<'a> {
+    post_core::TokenEndpoint {
+        url: TOKEN_URL,
+        client_id,
+        client_secret,
+        user_agent: Some(super::USER_AGENT),
     }
-
-    let token: TokenResponse = response
-        .json()
-        .await
-        .wrap_err("failed to parse Reddit token response")?;
-
-    Ok((token.access_token, token.refresh_token))
 }
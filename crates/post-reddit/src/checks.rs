@@ -0,0 +1,408 @@
+/// A post about to be submitted, described for [`super::Reddit::check`]
+pub struct Draft<'a> {
+    pub subreddit: &'a str,
+    pub title: &'a str,
+    pub kind: DraftKind<'a>,
+    /// Whether a flair was chosen
+    pub flair: bool,
+}
+
+pub enum DraftKind<'a> {
+    Text(Option<&'a str>),
+    Link(&'a str),
+    Image,
+    Gallery(&'a [super::MediaFile]),
+    Video,
+}
+
+impl super::Reddit {
+    /// Check a post against the subreddit's post requirements, failing with
+    /// every problem found so the post can be fixed before Reddit rejects it.
+    pub async fn check(&self, draft: &Draft<'_>) -> eyre::Result<()> {
+        let subreddit = draft.subreddit;
+        let requirements = self
+            .authorized(|client| async move { client.post_requirements(subreddit).await })
+            .await?;
+
+        let problems = problems(&requirements, draft);
+        if problems.is_empty() {
+            return Ok(());
+        }
+        Err(post_core::Error::Validation(format!(
+            "r/{subreddit} would reject this post:\n  - {}\n(see `post reddit rules -r {subreddit}`, or submit anyway with `post reddit post --skip-checks`)",
+            problems.join("\n  - ")
+        ))
+        .into())
+    }
+
+    /// A subreddit's rules and post requirements.
+    pub async fn rules(
+        &self,
+        subreddit: &str,
+    ) -> eyre::Result<(Vec<super::Rule>, super::PostRequirements)> {
+        let rules = self.authorized(|client| async move { client.rules(subreddit).await });
+        let requirements =
+            self.authorized(|client| async move { client.post_requirements(subreddit).await });
+        let (rules, requirements) = tokio::join!(rules, requirements);
+        Ok((rules?, requirements?))
+    }
+}
+
+/// Everything about the draft that breaks the requirements
+fn problems(requirements: &super::PostRequirements, draft: &Draft<'_>) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    check_text(
+        &mut problems,
+        "title",
+        draft.title,
+        TextRules {
+            min: requirements.title_text_min_length,
+            max: requirements.title_text_max_length,
+            required: &requirements.title_required_strings,
+            banned: &requirements.title_blacklisted_strings,
+            regexes: &requirements.title_regexes,
+        },
+    );
+
+    if requirements.is_flair_required && !draft.flair {
+        problems.push("a flair is required (pick one with --flair)".to_string());
+    }
+
+    match draft.kind {
+        DraftKind::Text(body) => {
+            let body = body.unwrap_or_default();
+            match requirements.body_restriction_policy.as_deref() {
+                Some("required") if body.trim().is_empty() => {
+                    problems.push("a body is required".to_string());
+                }
+                Some("notAllowed") if !body.is_empty() => {
+                    problems.push("text posts must not have a body".to_string());
+                }
+                _ => {}
+            }
+            if !body.is_empty() {
+                check_text(
+                    &mut problems,
+                    "body",
+                    body,
+                    TextRules {
+                        min: requirements.body_text_min_length,
+                        max: requirements.body_text_max_length,
+                        required: &requirements.body_required_strings,
+                        banned: &requirements.body_blacklisted_strings,
+                        regexes: &requirements.body_regexes,
+                    },
+                );
+            }
+        }
+        DraftKind::Link(link) => check_domain(&mut problems, requirements, link),
+        DraftKind::Gallery(items) => check_gallery(&mut problems, requirements, items),
+        DraftKind::Image | DraftKind::Video => {}
+    }
+
+    problems
+}
+
+/// Requirements on a title or body
+struct TextRules<'a> {
+    min: Option<usize>,
+    max: Option<usize>,
+    required: &'a [String],
+    banned: &'a [String],
+    regexes: &'a [String],
+}
+
+fn check_text(problems: &mut Vec<String>, field: &str, text: &str, rules: TextRules<'_>) {
+    let len = text.chars().count();
+    if let Some(min) = rules.min
+        && len < min
+    {
+        problems.push(format!(
+            "the {field} is {len} characters, the minimum is {min}"
+        ));
+    }
+    if let Some(max) = rules.max
+        && len > max
+    {
+        problems.push(format!(
+            "the {field} is {len} characters, the maximum is {max}"
+        ));
+    }
+
+    let lower = text.to_lowercase();
+    if !rules.required.is_empty()
+        && !rules
+            .required
+            .iter()
+            .any(|required| lower.contains(&required.to_lowercase()))
+    {
+        problems.push(format!(
+            "the {field} must contain one of: {}",
+            quoted(rules.required)
+        ));
+    }
+    let banned: Vec<_> = rules
+        .banned
+        .iter()
+        .filter(|banned| lower.contains(&banned.to_lowercase()))
+        .cloned()
+        .collect();
+    if !banned.is_empty() {
+        problems.push(format!("the {field} must not contain: {}", quoted(&banned)));
+    }
+
+    // Reddit uses JavaScript regexes; skip any the regex crate can't parse
+    let regexes: Vec<_> = rules
+        .regexes
+        .iter()
+        .filter_map(|pattern| match regex::Regex::new(pattern) {
+            Ok(regex) => Some(regex),
+            Err(error) => {
+                tracing::debug!("skipping {field} regex {pattern:?}: {error}");
+                None
+            }
+        })
+        .collect();
+    if !regexes.is_empty() && !regexes.iter().any(|regex| regex.is_match(text)) {
+        problems.push(format!(
+            "the {field} must match one of: {}",
+            quoted(rules.regexes)
+        ));
+    }
+}
+
+fn check_domain(problems: &mut Vec<String>, requirements: &super::PostRequirements, link: &str) {
+    let Some(host) = reqwest::Url::parse(link)
+        .ok()
+        .and_then(|url| url.host_str().map(str::to_lowercase))
+    else {
+        return;
+    };
+    let matches = |domain: &String| {
+        let domain = domain.to_lowercase();
+        host == domain || host.ends_with(&format!(".{domain}"))
+    };
+
+    match requirements.link_restriction_policy.as_deref() {
+        Some("whitelist") if !requirements.domain_whitelist.iter().any(matches) => {
+            problems.push(format!(
+                "links to {host} are not allowed (allowed: {})",
+                requirements.domain_whitelist.join(", ")
+            ));
+        }
+        Some("blacklist") if requirements.domain_blacklist.iter().any(matches) => {
+            problems.push(format!("links to {host} are not allowed"));
+        }
+        _ => {}
+    }
+}
+
+fn check_gallery(
+    problems: &mut Vec<String>,
+    requirements: &super::PostRequirements,
+    items: &[super::MediaFile],
+) {
+    if let Some(min) = requirements.gallery_min_items
+        && items.len() < min
+    {
+        problems.push(format!("galleries need at least {min} images"));
+    }
+    if let Some(max) = requirements.gallery_max_items
+        && items.len() > max
+    {
+        problems.push(format!("galleries can have at most {max} images"));
+    }
+    if requirements.gallery_captions_requirement.as_deref() == Some("required")
+        && items.iter().any(|item| item.caption.is_none())
+    {
+        problems.push("every gallery image needs a --caption".to_string());
+    }
+    if requirements.gallery_urls_requirement.as_deref() == Some("required")
+        && items.iter().any(|item| item.outbound_url.is_none())
+    {
+        problems.push("every gallery image needs an --outbound-url".to_string());
+    }
+}
+
+fn quoted(values: &[String]) -> String {
+    values
+        .iter()
+        .map(|value| format!("`{value}`"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Draft, DraftKind, problems};
+    use crate::{MediaFile, PostRequirements};
+
+    fn text_post<'a>(title: &'a str, body: Option<&'a str>) -> Draft<'a> {
+        Draft {
+            subreddit: "rust",
+            title,
+            kind: DraftKind::Text(body),
+            flair: false,
+        }
+    }
+
+    fn strings(values: &[&str]) -> Vec<String> {
+        values.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn checks_title_and_body_length() {
+        let requirements = PostRequirements {
+            title_text_min_length: Some(5),
+            title_text_max_length: Some(10),
+            body_text_min_length: Some(3),
+            body_text_max_length: Some(6),
+            ..Default::default()
+        };
+        assert!(problems(&requirements, &text_post("Hello", Some("abc"))).is_empty());
+        assert!(problems(&requirements, &text_post("Hello", None)).is_empty());
+        assert_eq!(
+            problems(&requirements, &text_post("Hi", Some("ab"))),
+            [
+                "the title is 2 characters, the minimum is 5",
+                "the body is 2 characters, the minimum is 3",
+            ]
+        );
+        assert_eq!(
+            problems(&requirements, &text_post("Hello world", Some("abcdefg"))),
+            [
+                "the title is 11 characters, the maximum is 10",
+                "the body is 7 characters, the maximum is 6",
+            ]
+        );
+    }
+
+    #[test]
+    fn checks_required_and_banned_strings_ignoring_case() {
+        let requirements = PostRequirements {
+            title_required_strings: strings(&["[OC]", "[Project]"]),
+            title_blacklisted_strings: strings(&["Help"]),
+            body_blacklisted_strings: strings(&["discord.gg"]),
+            ..Default::default()
+        };
+        assert!(problems(&requirements, &text_post("[project] post", None)).is_empty());
+        assert_eq!(
+            problems(
+                &requirements,
+                &text_post("need HELP", Some("Join Discord.GG/x"))
+            ),
+            [
+                "the title must contain one of: `[OC]`, `[Project]`",
+                "the title must not contain: `Help`",
+                "the body must not contain: `discord.gg`",
+            ]
+        );
+    }
+
+    #[test]
+    fn checks_regexes_skipping_unsupported_ones() {
+        // Lookahead is valid in JavaScript but not in the regex crate
+        let requirements = PostRequirements {
+            title_regexes: strings(&[r"^(?=.*\d)", r"^\[\w+\]"]),
+            ..Default::default()
+        };
+        assert!(problems(&requirements, &text_post("[Rust] 1.0", None)).is_empty());
+        assert_eq!(
+            problems(&requirements, &text_post("Rust 1.0", None)),
+            [r"the title must match one of: `^(?=.*\d)`, `^\[\w+\]`"]
+        );
+
+        let unsupported = PostRequirements {
+            title_regexes: strings(&[r"^(?=.*\d)"]),
+            ..Default::default()
+        };
+        assert!(problems(&unsupported, &text_post("no digits", None)).is_empty());
+    }
+
+    #[test]
+    fn checks_domains() {
+        let link = |url| Draft {
+            subreddit: "rust",
+            title: "A link",
+            kind: DraftKind::Link(url),
+            flair: false,
+        };
+        let whitelist = PostRequirements {
+            link_restriction_policy: Some("whitelist".to_string()),
+            domain_whitelist: strings(&["github.com"]),
+            ..Default::default()
+        };
+        assert!(problems(&whitelist, &link("https://github.com/a/b")).is_empty());
+        assert!(problems(&whitelist, &link("https://Gist.GitHub.com/a")).is_empty());
+        assert_eq!(
+            problems(&whitelist, &link("https://notgithub.com/a")),
+            ["links to notgithub.com are not allowed (allowed: github.com)"]
+        );
+
+        let blacklist = PostRequirements {
+            link_restriction_policy: Some("blacklist".to_string()),
+            domain_blacklist: strings(&["youtube.com"]),
+            ..Default::default()
+        };
+        assert!(problems(&blacklist, &link("https://github.com/a")).is_empty());
+        assert_eq!(
+            problems(&blacklist, &link("https://www.youtube.com/watch")),
+            ["links to www.youtube.com are not allowed"]
+        );
+    }
+
+    #[test]
+    fn checks_galleries() {
+        let image = |caption: Option<&str>, url: Option<&str>| MediaFile {
+            path: "image.png".into(),
+            caption: caption.map(str::to_string),
+            outbound_url: url.map(str::to_string),
+        };
+        let requirements = PostRequirements {
+            gallery_min_items: Some(2),
+            gallery_captions_requirement: Some("required".to_string()),
+            gallery_urls_requirement: Some("required".to_string()),
+            ..Default::default()
+        };
+        let gallery = |items| Draft {
+            subreddit: "rust",
+            title: "Pictures",
+            kind: DraftKind::Gallery(items),
+            flair: false,
+        };
+
+        let complete = [
+            image(Some("One"), Some("https://a.com")),
+            image(Some("Two"), Some("https://b.com")),
+        ];
+        assert!(problems(&requirements, &gallery(&complete)).is_empty());
+        assert_eq!(
+            problems(&requirements, &gallery(&[image(None, None)])),
+            [
+                "galleries need at least 2 images",
+                "every gallery image needs a --caption",
+                "every gallery image needs an --outbound-url",
+            ]
+        );
+    }
+
+    #[test]
+    fn checks_body_policy() {
+        let policy = |policy: &str| PostRequirements {
+            body_restriction_policy: Some(policy.to_string()),
+            ..Default::default()
+        };
+        assert!(problems(&policy("required"), &text_post("Title", Some("Body"))).is_empty());
+        assert_eq!(
+            problems(&policy("required"), &text_post("Title", Some("  "))),
+            ["a body is required"]
+        );
+        assert!(problems(&policy("notAllowed"), &text_post("Title", None)).is_empty());
+        assert_eq!(
+            problems(&policy("notAllowed"), &text_post("Title", Some("Body"))),
+            ["text posts must not have a body"]
+        );
+        assert!(problems(&policy("none"), &text_post("Title", None)).is_empty());
+    }
+}
@@ -1,28 +1,278 @@
-use eyre::WrapErr as _;
-
 const SUBMIT_URL: &str = "https://oauth.reddit.com/api/submit";
+const SUBMIT_GALLERY_URL: &str = "https://oauth.reddit.com/api/submit_gallery_post.json";
+const MEDIA_ASSET_URL: &str = "https://oauth.reddit.com/api/media/asset.json";
+const API_BASE: &str = "https://oauth.reddit.com";
 
 pub struct Client {
     access_token: String,
     http: reqwest::Client,
 }
 
+/// Response shape shared by `api_type=json` endpoints
 #[derive(serde::Deserialize)]
-struct SubmitResponse {
-    json: SubmitJson,
+struct ApiResponse<T> {
+    json: ApiJson<T>,
 }
 
 #[derive(serde::Deserialize)]
-struct SubmitJson {
+struct ApiJson<T> {
+    #[serde(default)]
     errors: Vec<Vec<String>>,
-    data: Option<SubmitData>,
+    data: Option<T>,
+}
+
+/// The authenticated Reddit user
+#[derive(serde::Deserialize)]
+pub struct Account {
+    pub name: String,
+}
+
+/// Flair to attach to a submission
+pub struct Flair {
+    /// Template ID from [`Client::link_flairs`]
+    pub id: String,
+    /// Custom text, for flairs that allow editing
+    pub text: Option<String>,
+}
+
+/// Optional settings for a new submission
+pub struct SubmitOptions {
+    pub flair: Option<Flair>,
+    /// Mark the post as not safe for work
+    pub nsfw: bool,
+    /// Hide the post's content behind a spoiler warning
+    pub spoiler: bool,
+    /// Send comment replies to the inbox
+    pub send_replies: bool,
+    /// Allow submitting a link that was already posted to the subreddit
+    pub resubmit: bool,
+    /// Tag the post as original content
+    pub original_content: bool,
+}
+
+impl Default for SubmitOptions {
+    fn default() -> Self {
+        Self {
+            flair: None,
+            nsfw: false,
+            spoiler: false,
+            send_replies: true,
+            resubmit: false,
+            original_content: false,
+        }
+    }
+}
+
+impl SubmitOptions {
+    fn push_params<'a>(&'a self, params: &mut Vec<(&str, &'a str)>) {
+        if let Some(flair) = &self.flair {
+            params.push(("flair_id", &flair.id));
+            if let Some(text) = &flair.text {
+                params.push(("flair_text", text));
+            }
+        }
+        let flags = [
+            ("nsfw", self.nsfw),
+            ("spoiler", self.spoiler),
+            ("sendreplies", self.send_replies),
+            ("resubmit", self.resubmit),
+        ];
+        for (name, value) in flags {
+            params.push((name, if value { "true" } else { "false" }));
+        }
+    }
+}
+
+/// JSON body of `submit_gallery_post`, which takes real booleans where the
+/// form-encoded endpoints take `"true"`/`"false"`
+#[derive(serde::Serialize)]
+struct GalleryRequest<'a> {
+    api_type: &'static str,
+    show_error_list: bool,
+    sr: &'a str,
+    title: &'a str,
+    items: &'a [GalleryItem],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    flair_id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    flair_text: Option<&'a str>,
+    nsfw: bool,
+    spoiler: bool,
+    sendreplies: bool,
+    resubmit: bool,
+}
+
+/// A link flair template offered by a subreddit
+#[derive(serde::Deserialize)]
+pub struct LinkFlair {
+    pub id: String,
+    pub text: String,
+    /// Whether posters may replace the text
+    pub text_editable: bool,
+    /// Whether only moderators may use this flair
+    #[serde(default)]
+    pub mod_only: bool,
+}
+
+/// What a subreddit requires of new posts, from `post_requirements`
+#[derive(serde::Deserialize, Default)]
+#[serde(default)]
+pub struct PostRequirements {
+    pub title_text_min_length: Option<usize>,
+    pub title_text_max_length: Option<usize>,
+    /// The title must contain at least one of these
+    pub title_required_strings: Vec<String>,
+    pub title_blacklisted_strings: Vec<String>,
+    /// The title must match at least one of these
+    pub title_regexes: Vec<String>,
+    /// `none`, `required` or `notAllowed`
+    pub body_restriction_policy: Option<String>,
+    pub body_text_min_length: Option<usize>,
+    pub body_text_max_length: Option<usize>,
+    /// The body must contain at least one of these
+    pub body_required_strings: Vec<String>,
+    pub body_blacklisted_strings: Vec<String>,
+    /// The body must match at least one of these
+    pub body_regexes: Vec<String>,
+    /// `none`, `whitelist` or `blacklist`
+    pub link_restriction_policy: Option<String>,
+    pub domain_whitelist: Vec<String>,
+    pub domain_blacklist: Vec<String>,
+    pub is_flair_required: bool,
+    pub gallery_min_items: Option<usize>,
+    pub gallery_max_items: Option<usize>,
+    /// `none`, `optional` or `required`
+    pub gallery_captions_requirement: Option<String>,
+    /// `none`, `optional` or `required`
+    pub gallery_urls_requirement: Option<String>,
+    pub guidelines_text: Option<String>,
 }
 
+/// A subreddit rule, from `about/rules`
 #[derive(serde::Deserialize)]
-struct SubmitData {
+pub struct Rule {
+    pub short_name: String,
+    #[serde(default)]
+    pub description: String,
+    /// `link`, `comment` or `all`
+    pub kind: String,
+}
+
+#[derive(serde::Deserialize)]
+struct Rules {
+    rules: Vec<Rule>,
+}
+
+/// A newly created submission
+#[derive(serde::Deserialize)]
+pub struct Submission {
+    /// Fullname of the post, e.g. `t3_abc123`
+    pub name: String,
+    pub url: String,
+}
+
+#[derive(serde::Deserialize)]
+struct GallerySubmission {
+    id: String,
     url: String,
 }
 
+/// One image in a gallery post
+#[derive(serde::Serialize)]
+pub struct GalleryItem {
+    /// Asset ID from [`Client::upload_media`]
+    pub media_id: String,
+    pub caption: String,
+    pub outbound_url: String,
+}
+
+/// Media uploaded through the asset lease flow
+pub struct UploadedMedia {
+    /// ID used to reference the media in gallery posts
+    pub asset_id: String,
+    /// Hosted URL used in image and video posts
+    pub url: String,
+}
+
+#[derive(serde::Deserialize)]
+struct MediaLease {
+    args: LeaseArgs,
+    asset: LeaseAsset,
+}
+
+/// Where and how to upload the file
+#[derive(serde::Deserialize)]
+struct LeaseArgs {
+    action: String,
+    fields: Vec<LeaseField>,
+}
+
+#[derive(serde::Deserialize)]
+struct LeaseField {
+    name: String,
+    value: String,
+}
+
+#[derive(serde::Deserialize)]
+struct LeaseAsset {
+    asset_id: String,
+}
+
+/// A page of things from a listing endpoint
+#[derive(serde::Deserialize)]
+pub struct Listing<T> {
+    data: ListingData<T>,
+}
+
+#[derive(serde::Deserialize)]
+struct ListingData<T> {
+    children: Vec<Thing<T>>,
+}
+
+#[derive(serde::Deserialize)]
+struct Thing<T> {
+    data: T,
+}
+
+impl<T> Listing<T> {
+    pub fn into_items(self) -> impl Iterator<Item = T> {
+        self.data.children.into_iter().map(|child| child.data)
+    }
+}
+
+/// A newly created comment
+#[derive(serde::Deserialize)]
+pub struct Comment {
+    /// Fullname of the comment, e.g. `t1_abc123`
+    pub name: String,
+    /// Path of the comment, relative to reddit.com
+    pub permalink: String,
+}
+
+#[derive(serde::Deserialize)]
+struct CommentData {
+    things: Vec<Thing<Comment>>,
+}
+
+/// A submitted post, as returned by listings
+#[derive(serde::Deserialize)]
+pub struct Link {
+    /// Fullname of the post, e.g. `t3_abc123`
+    pub name: String,
+    pub title: String,
+    pub subreddit: String,
+    pub permalink: String,
+    pub created_utc: f64,
+    #[serde(default)]
+    pub score: i64,
+}
+
+impl Link {
+    pub fn permalink_url(&self) -> String {
+        format!("https://www.reddit.com{}", self.permalink)
+    }
+}
+
 impl Client {
     pub fn new(access_token: String) -> Self {
         Self {
@@ -37,7 +287,8 @@ impl Client {
         subreddit: &str,
         title: &str,
         text: Option<&str>,
-    ) -> eyre::Result<String> {
+        options: &SubmitOptions,
+    ) -> Result<Submission, post_core::Error> {
         let mut params = vec![
             ("api_type", "json"),
             ("kind", "self"),
@@ -48,6 +299,7 @@ impl Client {
         if let Some(text) = text {
             params.push(("text", text));
         }
+        options.push_params(&mut params);
 
         self.submit(&params).await
     }
@@ -58,55 +310,469 @@ impl Client {
         subreddit: &str,
         title: &str,
         url: &str,
-    ) -> eyre::Result<String> {
-        let params = [
+        options: &SubmitOptions,
+    ) -> Result<Submission, post_core::Error> {
+        let mut params = vec![
             ("api_type", "json"),
             ("kind", "link"),
             ("sr", subreddit),
             ("title", title),
             ("url", url),
         ];
+        options.push_params(&mut params);
+
+        self.submit(&params).await
+    }
+
+    /// Crosspost an existing post, given its `t3_` fullname, to a subreddit.
+    pub async fn crosspost(
+        &self,
+        subreddit: &str,
+        title: &str,
+        source_fullname: &str,
+    ) -> Result<Submission, post_core::Error> {
+        let params = [
+            ("api_type", "json"),
+            ("kind", "crosspost"),
+            ("sr", subreddit),
+            ("title", title),
+            ("crosspost_fullname", source_fullname),
+        ];
 
         self.submit(&params).await
     }
 
-    async fn submit(&self, params: &[(&str, &str)]) -> eyre::Result<String> {
+    /// Submit an image post for media uploaded with [`Client::upload_media`].
+    ///
+    /// Reddit creates media posts asynchronously, so no post is returned;
+    /// look it up with [`Client::submitted`] once processing finishes.
+    pub async fn submit_image_post(
+        &self,
+        subreddit: &str,
+        title: &str,
+        image_url: &str,
+        options: &SubmitOptions,
+    ) -> Result<(), post_core::Error> {
+        let mut params = vec![
+            ("api_type", "json"),
+            ("kind", "image"),
+            ("sr", subreddit),
+            ("title", title),
+            ("url", image_url),
+        ];
+        options.push_params(&mut params);
+
+        self.send_api::<serde::de::IgnoredAny>(self.http.post(SUBMIT_URL).form(&params))
+            .await?;
+        Ok(())
+    }
+
+    /// Submit a video post. Reddit requires a poster image shown before playback.
+    ///
+    /// Like image posts, the post is created asynchronously.
+    pub async fn submit_video_post(
+        &self,
+        subreddit: &str,
+        title: &str,
+        video_url: &str,
+        poster_url: &str,
+        options: &SubmitOptions,
+    ) -> Result<(), post_core::Error> {
+        let mut params = vec![
+            ("api_type", "json"),
+            ("kind", "video"),
+            ("sr", subreddit),
+            ("title", title),
+            ("url", video_url),
+            ("video_poster_url", poster_url),
+        ];
+        options.push_params(&mut params);
+
+        self.send_api::<serde::de::IgnoredAny>(self.http.post(SUBMIT_URL).form(&params))
+            .await?;
+        Ok(())
+    }
+
+    /// Submit a gallery of images uploaded with [`Client::upload_media`].
+    pub async fn submit_gallery_post(
+        &self,
+        subreddit: &str,
+        title: &str,
+        items: &[GalleryItem],
+        options: &SubmitOptions,
+    ) -> Result<Submission, post_core::Error> {
+        let flair = options.flair.as_ref();
+        let body = GalleryRequest {
+            api_type: "json",
+            show_error_list: true,
+            sr: subreddit,
+            title,
+            items,
+            flair_id: flair.map(|flair| flair.id.as_str()),
+            flair_text: flair.and_then(|flair| flair.text.as_deref()),
+            nsfw: options.nsfw,
+            spoiler: options.spoiler,
+            sendreplies: options.send_replies,
+            resubmit: options.resubmit,
+        };
+
+        let gallery = self
+            .send_api::<GallerySubmission>(self.http.post(SUBMIT_GALLERY_URL).json(&body))
+            .await?
+            .ok_or_else(no_data)?;
+
+        Ok(Submission {
+            name: gallery.id,
+            url: gallery.url,
+        })
+    }
+
+    /// Upload a file through Reddit's media asset lease: request an upload
+    /// lease, then post the file to the storage bucket it names.
+    pub async fn upload_media(
+        &self,
+        filename: &str,
+        mime_type: &str,
+        data: &[u8],
+    ) -> Result<UploadedMedia, post_core::Error> {
+        let params = [("filepath", filename), ("mimetype", mime_type)];
+        let lease: MediaLease = self
+            .send(self.http.post(MEDIA_ASSET_URL).form(&params))
+            .await?
+            .json()
+            .await
+            .map_err(post_core::Error::decode)?;
+
+        // The action is protocol-relative, e.g. `//reddit-uploaded-media.s3-accelerate.amazonaws.com`
+        let action = format!("https:{}", lease.args.action);
+        let mut key = None;
+        let mut form = reqwest::multipart::Form::new();
+        for field in lease.args.fields {
+            if field.name == "key" {
+                key = Some(field.value.clone());
+            }
+            form = form.text(field.name, field.value);
+        }
+        let file = reqwest::multipart::Part::bytes(data.to_vec())
+            .file_name(filename.to_string())
+            .mime_str(mime_type)
+            .map_err(|_| {
+                post_core::Error::Validation(format!("unsupported media type {mime_type}"))
+            })?;
+        form = form.part("file", file);
+
         let response = self
             .http
-            .post(SUBMIT_URL)
-            .header("Authorization", format!("Bearer {}", self.access_token))
+            .post(&action)
             .header("User-Agent", super::USER_AGENT)
-            .form(params)
+            .multipart(form)
             .send()
             .await
-            .wrap_err("failed to submit Reddit post")?;
-
+            .map_err(post_core::Error::network)?;
         if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            eyre::bail!("Reddit submission failed ({status}): {body}");
+            return Err(error_from_response(response).await);
         }
 
-        let submit: SubmitResponse = response
+        let key = key.ok_or_else(|| post_core::Error::PlatformError {
+            code: "NO_KEY".to_string(),
+            message: "Reddit upload lease did not include a key".to_string(),
+        })?;
+        Ok(UploadedMedia {
+            asset_id: lease.asset.asset_id,
+            url: format!("{action}/{key}"),
+        })
+    }
+
+    /// Reply to a post or comment, given its fullname, with Markdown text.
+    pub async fn comment(
+        &self,
+        parent_fullname: &str,
+        markdown: &str,
+    ) -> Result<Comment, post_core::Error> {
+        let params = [
+            ("api_type", "json"),
+            ("thing_id", parent_fullname),
+            ("text", markdown),
+        ];
+        let data: CommentData = self
+            .send_api(
+                self.http
+                    .post(format!("{API_BASE}/api/comment"))
+                    .form(&params),
+            )
+            .await?
+            .ok_or_else(no_data)?;
+        data.things
+            .into_iter()
+            .next()
+            .map(|thing| thing.data)
+            .ok_or_else(no_data)
+    }
+
+    /// Replace the text of a self post or comment, given its fullname.
+    pub async fn edit(&self, fullname: &str, markdown: &str) -> Result<(), post_core::Error> {
+        let params = [
+            ("api_type", "json"),
+            ("thing_id", fullname),
+            ("text", markdown),
+        ];
+        self.send_api::<serde::de::IgnoredAny>(
+            self.http
+                .post(format!("{API_BASE}/api/editusertext"))
+                .form(&params),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Delete a post or comment, given its fullname.
+    pub async fn delete(&self, fullname: &str) -> Result<(), post_core::Error> {
+        self.send(
+            self.http
+                .post(format!("{API_BASE}/api/del"))
+                .form(&[("id", fullname)]),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Tag a post as original content. The endpoint is undocumented; these
+    /// are the parameters Reddit's own clients send.
+    pub async fn set_original_content(
+        &self,
+        fullname: &str,
+        subreddit: &str,
+    ) -> Result<(), post_core::Error> {
+        let id = fullname.strip_prefix("t3_").unwrap_or(fullname);
+        let params = [
+            ("id", id),
+            ("fullname", fullname),
+            ("should_set_oc", "true"),
+            ("executed", "false"),
+            ("r", subreddit),
+        ];
+        self.send(
+            self.http
+                .post(format!("{API_BASE}/api/set_original_content"))
+                .form(&params),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Link flairs available in a subreddit.
+    pub async fn link_flairs(&self, subreddit: &str) -> Result<Vec<LinkFlair>, post_core::Error> {
+        let url = format!("{API_BASE}/r/{subreddit}/api/link_flair_v2");
+        self.send(self.http.get(url).query(&[("raw_json", "1")]))
+            .await?
+            .json()
+            .await
+            .map_err(post_core::Error::decode)
+    }
+
+    /// What a subreddit requires of new posts.
+    pub async fn post_requirements(
+        &self,
+        subreddit: &str,
+    ) -> Result<PostRequirements, post_core::Error> {
+        let url = format!("{API_BASE}/api/v1/{subreddit}/post_requirements");
+        self.send(self.http.get(url))
+            .await?
+            .json()
+            .await
+            .map_err(post_core::Error::decode)
+    }
+
+    /// A subreddit's rules, in order.
+    pub async fn rules(&self, subreddit: &str) -> Result<Vec<Rule>, post_core::Error> {
+        let url = format!("{API_BASE}/r/{subreddit}/about/rules");
+        let rules: Rules = self
+            .send(self.http.get(url).query(&[("raw_json", "1")]))
+            .await?
+            .json()
+            .await
+            .map_err(post_core::Error::decode)?;
+        Ok(rules.rules)
+    }
+
+    /// Look up posts by fullname.
+    pub async fn info(&self, fullnames: &[&str]) -> Result<Vec<Link>, post_core::Error> {
+        let ids = fullnames.join(",");
+        let listing: Listing<Link> = self
+            .send(
+                self.http
+                    .get(format!("{API_BASE}/api/info"))
+                    .query(&[("id", ids.as_str()), ("raw_json", "1")]),
+            )
+            .await?
+            .json()
+            .await
+            .map_err(post_core::Error::decode)?;
+        Ok(listing.into_items().collect())
+    }
+
+    /// Posts linking to a URL.
+    pub async fn find_by_url(&self, url: &str) -> Result<Vec<Link>, post_core::Error> {
+        let listing: Listing<Link> = self
+            .send(
+                self.http
+                    .get(format!("{API_BASE}/api/info"))
+                    .query(&[("url", url), ("raw_json", "1")]),
+            )
+            .await?
+            .json()
+            .await
+            .map_err(post_core::Error::decode)?;
+        Ok(listing.into_items().collect())
+    }
+
+    /// Other submissions of the same link as a post.
+    pub async fn duplicates(&self, fullname: &str) -> Result<Vec<Link>, post_core::Error> {
+        let id = fullname.trim_start_matches("t3_");
+        // The response holds two listings: the post itself, then its duplicates
+        let (_, duplicates): (Listing<Link>, Listing<Link>) = self
+            .send(
+                self.http
+                    .get(format!("{API_BASE}/duplicates/{id}"))
+                    .query(&[("raw_json", "1")]),
+            )
+            .await?
+            .json()
+            .await
+            .map_err(post_core::Error::decode)?;
+        Ok(duplicates.into_items().collect())
+    }
+
+    /// A user's most recent submissions, newest first.
+    pub async fn submitted(
+        &self,
+        username: &str,
+        limit: u32,
+    ) -> Result<Vec<Link>, post_core::Error> {
+        let url = format!("{API_BASE}/user/{username}/submitted");
+        let limit = limit.to_string();
+        let listing: Listing<Link> = self
+            .send(self.http.get(url).query(&[
+                ("sort", "new"),
+                ("limit", limit.as_str()),
+                ("raw_json", "1"),
+            ]))
+            .await?
+            .json()
+            .await
+            .map_err(post_core::Error::decode)?;
+        Ok(listing.into_items().collect())
+    }
+
+    /// The account the access token belongs to.
+    pub async fn me(&self) -> Result<Account, post_core::Error> {
+        self.send(self.http.get(format!("{API_BASE}/api/v1/me")))
+            .await?
+            .json()
+            .await
+            .map_err(post_core::Error::decode)
+    }
+
+    async fn submit(&self, params: &[(&str, &str)]) -> Result<Submission, post_core::Error> {
+        self.send_api(self.http.post(SUBMIT_URL).form(params))
+            .await?
+            .ok_or_else(no_data)
+    }
+
+    /// Send a request to an `api_type=json` endpoint and unwrap its `json`
+    /// envelope, turning reported errors into typed errors.
+    async fn send_api<T: serde::de::DeserializeOwned>(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<Option<T>, post_core::Error> {
+        let response: ApiResponse<T> = self
+            .send(request)
+            .await?
             .json()
             .await
-            .wrap_err("failed to parse Reddit submit response")?;
-
-        if !submit.json.errors.is_empty() {
-            let errors: Vec<String> = submit
-                .json
-                .errors
-                .iter()
-                .map(|e| e.join(": "))
-                .collect();
-            eyre::bail!("Reddit submission errors: {}", errors.join(", "));
+            .map_err(post_core::Error::decode)?;
+
+        if let Some(error) = response.json.errors.first() {
+            return Err(classify_api_error(error));
+        }
+
+        Ok(response.json.data)
+    }
+
+    /// Send an authenticated request, turning HTTP failures into typed errors.
+    async fn send(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, post_core::Error> {
+        let response = request
+            .header("Authorization", format!("Bearer {}", self.access_token))
+            .header("User-Agent", super::USER_AGENT)
+            .send()
+            .await
+            .map_err(post_core::Error::network)?;
+
+        if !response.status().is_success() {
+            return Err(error_from_response(response).await);
         }
 
-        let data = submit
-            .json
-            .data
-            .ok_or_else(|| eyre::eyre!("Reddit submission succeeded but returned no URL"))?;
+        Ok(response)
+    }
+}
+
+fn no_data() -> post_core::Error {
+    post_core::Error::PlatformError {
+        code: "NO_DATA".to_string(),
+        message: "Reddit request succeeded but returned no data".to_string(),
+    }
+}
 
-        Ok(data.url)
+/// Classify a failed Reddit HTTP response.
+async fn error_from_response(response: reqwest::Response) -> post_core::Error {
+    let status = response.status();
+    // Reddit reports the seconds remaining in the current rate limit window
+    let reset = response
+        .headers()
+        .get("x-ratelimit-reset")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok())
+        .map(|secs| std::time::SystemTime::now() + std::time::Duration::from_secs(secs));
+    let body = response.text().await.unwrap_or_default();
+
+    match status {
+        reqwest::StatusCode::UNAUTHORIZED => post_core::Error::Unauthorized(body),
+        reqwest::StatusCode::TOO_MANY_REQUESTS => post_core::Error::RateLimited { reset },
+        reqwest::StatusCode::NOT_FOUND => post_core::Error::NotFound(body),
+        _ => post_core::Error::PlatformError {
+            code: status.as_u16().to_string(),
+            message: body,
+        },
+    }
+}
+
+/// Classify an entry of the `json.errors` array, shaped `[code, message, field]`.
+fn classify_api_error(error: &[String]) -> post_core::Error {
+    let code = error.first().cloned().unwrap_or_default();
+    let message = error.get(1).cloned().unwrap_or_default();
+
+    match code.as_str() {
+        "RATELIMIT" => post_core::Error::RateLimited { reset: None },
+        "ALREADY_SUB" => post_core::Error::Duplicate(message),
+        "USER_REQUIRED" => post_core::Error::Unauthorized(message),
+        "SUBMIT_VALIDATION_FLAIR_REQUIRED" => post_core::Error::Validation(format!(
+            "{message} — pick one with --flair (list them with `post reddit flairs`)"
+        )),
+        "BAD_SR_NAME"
+        | "SUBREDDIT_NOEXIST"
+        | "SUBREDDIT_NOTALLOWED"
+        | "NO_TEXT"
+        | "NO_URL"
+        | "BAD_URL"
+        | "TOO_LONG"
+        | "NO_SELFS"
+        | "NO_LINKS" => post_core::Error::Validation(format!("{code}: {message}")),
+        _ if code.starts_with("SUBMIT_VALIDATION") => {
+            post_core::Error::Validation(format!("{code}: {message}"))
+        }
+        _ => post_core::Error::PlatformError { code, message },
     }
 }
@@ -0,0 +1,62 @@
+impl super::Reddit {
+    /// Link flairs available in a subreddit.
+    pub async fn flairs(&self, subreddit: &str) -> eyre::Result<Vec<super::LinkFlair>> {
+        self.authorized(|client| async move { client.link_flairs(subreddit).await })
+            .await
+    }
+
+    /// Look up a flair by template ID or by its text (case-insensitive).
+    pub async fn resolve_flair(
+        &self,
+        subreddit: &str,
+        flair: &str,
+        text: Option<String>,
+    ) -> eyre::Result<super::Flair> {
+        let flairs = self.flairs(subreddit).await?;
+        let found = flairs
+            .iter()
+            .find(|candidate| candidate.id == flair)
+            .or_else(|| {
+                flairs
+                    .iter()
+                    .find(|candidate| candidate.text.eq_ignore_ascii_case(flair))
+            });
+
+        let Some(found) = found else {
+            let available = flairs
+                .iter()
+                .filter(|candidate| !candidate.mod_only)
+                .map(|candidate| format!("`{}`", candidate.text))
+                .collect::<Vec<_>>();
+            return Err(post_core::Error::Validation(if available.is_empty() {
+                format!("r/{subreddit} has no link flairs")
+            } else {
+                format!(
+                    "r/{subreddit} has no flair `{flair}` (available: {})",
+                    available.join(", ")
+                )
+            })
+            .into());
+        };
+
+        if found.mod_only {
+            return Err(post_core::Error::Validation(format!(
+                "flair `{}` in r/{subreddit} is only for moderators",
+                found.text
+            ))
+            .into());
+        }
+        if text.is_some() && !found.text_editable {
+            return Err(post_core::Error::Validation(format!(
+                "flair `{}` in r/{subreddit} does not allow custom text",
+                found.text
+            ))
+            .into());
+        }
+
+        Ok(super::Flair {
+            id: found.id.clone(),
+            text,
+        })
+    }
+}
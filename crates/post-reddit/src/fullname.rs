@@ -0,0 +1,133 @@
+/// Turn a Reddit URL, fullname or bare post ID into a fullname.
+///
+/// Accepts `t3_abc123`, `abc123`, `https://redd.it/abc123`, gallery links
+/// like `https://www.reddit.com/gallery/abc123` and permalinks like
+/// `https://www.reddit.com/r/rust/comments/abc123/title/`. A permalink to a
+/// comment gives the comment's `t1_` fullname.
+pub fn fullname(input: &str) -> eyre::Result<String> {
+    let input = input.trim();
+    if input.starts_with("t1_") || input.starts_with("t3_") {
+        return Ok(input.to_string());
+    }
+    if is_id(input) {
+        return Ok(format!("t3_{input}"));
+    }
+
+    let url = reqwest::Url::parse(input)
+        .map_err(|_| eyre::eyre!("`{input}` is not a Reddit URL or post ID"))?;
+    let host = url.host_str().unwrap_or_default();
+    let segments: Vec<_> = url
+        .path_segments()
+        .into_iter()
+        .flatten()
+        .filter(|segment| !segment.is_empty())
+        .collect();
+
+    if host == "redd.it" {
+        if let [id] = segments.as_slice()
+            && is_id(id)
+        {
+            return Ok(format!("t3_{id}"));
+        }
+    } else if host == "reddit.com" || host.ends_with(".reddit.com") {
+        if let ["gallery", id] = segments.as_slice()
+            && is_id(id)
+        {
+            return Ok(format!("t3_{id}"));
+        }
+        if let Some(position) = segments.iter().position(|segment| *segment == "comments") {
+            match &segments[position + 1..] {
+                [_, _, comment, ..] if is_id(comment) => return Ok(format!("t1_{comment}")),
+                [post, ..] if is_id(post) => return Ok(format!("t3_{post}")),
+                _ => {}
+            }
+        }
+        if segments.get(2) == Some(&"s") {
+            eyre::bail!("share links can't be resolved — open `{input}` and use the full URL");
+        }
+    }
+
+    eyre::bail!("`{input}` is not a link to a Reddit post")
+}
+
+/// Like [`fullname`], but only accepts posts.
+pub fn post_fullname(input: &str) -> eyre::Result<String> {
+    let fullname = fullname(input)?;
+    if !fullname.starts_with("t3_") {
+        eyre::bail!("`{input}` is a comment, not a post");
+    }
+    Ok(fullname)
+}
+
+/// Reddit IDs are lowercase base36.
+fn is_id(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_digit() || c.is_ascii_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fullname, post_fullname};
+
+    #[test]
+    fn parses_fullnames_ids_and_urls() {
+        let cases = [
+            ("t3_abc123", "t3_abc123"),
+            ("t1_def456", "t1_def456"),
+            ("abc123", "t3_abc123"),
+            ("  abc123\n", "t3_abc123"),
+            ("https://redd.it/abc123", "t3_abc123"),
+            ("https://www.reddit.com/gallery/abc123", "t3_abc123"),
+            ("https://reddit.com/gallery/abc123/", "t3_abc123"),
+            (
+                "https://www.reddit.com/r/rust/comments/abc123/my_crate/",
+                "t3_abc123",
+            ),
+            ("https://old.reddit.com/r/rust/comments/abc123", "t3_abc123"),
+            (
+                "https://www.reddit.com/r/rust/comments/abc123/my_crate/def456/",
+                "t1_def456",
+            ),
+            (
+                "https://www.reddit.com/r/rust/comments/abc123/my_crate/?utm_source=share",
+                "t3_abc123",
+            ),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(fullname(input).unwrap(), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn rejects_other_input() {
+        for input in [
+            "",
+            "ABC123",
+            "not a post",
+            "https://example.com/comments/abc123",
+            "https://www.reddit.com/r/rust/",
+            "https://www.reddit.com/gallery/",
+            "https://www.reddit.com/r/rust/s/AbCdEf",
+            "https://redd.it/",
+        ] {
+            assert!(fullname(input).is_err(), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn post_fullname_only_accepts_posts() {
+        assert_eq!(post_fullname("abc123").unwrap(), "t3_abc123");
+        assert_eq!(
+            post_fullname("https://www.reddit.com/gallery/abc123").unwrap(),
+            "t3_abc123"
+        );
+        for input in [
+            "t1_def456",
+            "https://www.reddit.com/r/rust/comments/abc123/my_crate/def456/",
+        ] {
+            assert!(post_fullname(input).is_err(), "{input}");
+        }
+    }
+}
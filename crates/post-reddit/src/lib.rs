@@ -1,135 +1,478 @@
+use eyre::WrapErr as _;
+
 mod auth;
+mod checks;
 mod client;
+mod flair;
+mod fullname;
+mod media;
 
-pub use auth::authenticate;
-pub use client::Client;
+pub use auth::{DEFAULT_SCOPES, authenticate, authorize};
+pub use checks::{Draft, DraftKind};
+pub use client::{
+    Account, Client, Comment, Flair, GalleryItem, Link, LinkFlair, Listing, PostRequirements, Rule,
+    Submission, SubmitOptions, UploadedMedia,
+};
+pub use fullname::{fullname, post_fullname};
+pub use media::MediaFile;
 
 const USER_AGENT: &str = concat!("post-cli/", env!("CARGO_PKG_VERSION"), " (by /u/andrewgazelka)");
+const MAX_TITLE_LEN: usize = 300;
 
-/// Authenticate with Reddit using password grant flow (for "script" apps).
-pub async fn auth(
-    client_id: &str,
-    client_secret: &str,
-    username: &str,
-    password: &str,
-) -> eyre::Result<post_core::Config> {
-    let (access_token, refresh_token) =
-        auth::authenticate(client_id, client_secret, username, password).await?;
-
-    let mut config = post_core::Config::load()?;
-    config.reddit = Some(post_core::RedditConfig {
-        client_id: client_id.to_string(),
-        client_secret: client_secret.to_string(),
-        username: username.to_string(),
-        access_token,
-        refresh_token,
-    });
-    config.save()?;
-
-    Ok(config)
+/// Reddit, posting as one of the saved accounts
+#[derive(Default)]
+pub struct Reddit {
+    /// Saved account to use, or the default account if `None`
+    account: Option<String>,
 }
 
-/// Submit a text post to a subreddit.
-pub async fn post(subreddit: &str, title: &str, text: Option<&str>) -> eyre::Result<post_core::PostResult> {
-    let mut config = post_core::Config::load()?;
+impl Reddit {
+    pub fn new(account: Option<String>) -> Self {
+        Self { account }
+    }
 
-    let reddit_config = config
-        .reddit
-        .as_ref()
-        .ok_or_else(|| eyre::eyre!("not authenticated with Reddit — run `post reddit auth` first"))?
-        .clone();
+    /// Submit a link post if a link is given, otherwise a text post.
+    pub async fn submit(
+        &self,
+        content: &post_core::Content,
+        options: &SubmitOptions,
+    ) -> eyre::Result<post_core::PostResult> {
+        let subreddit = content
+            .community
+            .as_deref()
+            .ok_or_else(|| eyre::eyre!("Reddit posts require a subreddit"))?;
 
-    let client = Client::new(reddit_config.access_token.clone());
+        let title = &content.text;
+        let submission = match &content.link {
+            Some(link) => {
+                self.authorized(|client| async move {
+                    client
+                        .submit_link_post(subreddit, title, link, options)
+                        .await
+                })
+                .await?
+            }
+            None => {
+                let text = content.body.as_deref();
+                self.authorized(|client| async move {
+                    client
+                        .submit_self_post(subreddit, title, text, options)
+                        .await
+                })
+                .await?
+            }
+        };
 
-    match client.submit_self_post(subreddit, title, text).await {
-        Ok(url) => Ok(post_core::PostResult { url }),
-        Err(e) => {
-            // Try token refresh
-            if let Some(refresh_token) = &reddit_config.refresh_token {
-                tracing::debug!("attempting token refresh");
+        let result = post_core::PostResult {
+            id: submission.name,
+            url: submission.url,
+        };
+        Ok(self.finish(result, subreddit, options).await)
+    }
 
-                if let Ok((new_access, new_refresh)) = auth::refresh(
-                    &reddit_config.client_id,
-                    &reddit_config.client_secret,
-                    refresh_token,
-                )
-                .await
-                {
-                    config.reddit = Some(post_core::RedditConfig {
-                        client_id: reddit_config.client_id,
-                        client_secret: reddit_config.client_secret,
-                        username: reddit_config.username,
-                        access_token: new_access.clone(),
-                        refresh_token: new_refresh,
-                    });
-                    config.save()?;
-
-                    let client = Client::new(new_access);
-                    let url = client.submit_self_post(subreddit, title, text).await?;
-                    return Ok(post_core::PostResult { url });
+    /// Earlier submissions of a link to a subreddit, found through Reddit's
+    /// URL search and the duplicates of each match.
+    pub async fn reposts(&self, subreddit: &str, url: &str) -> eyre::Result<Vec<Link>> {
+        let mut found = self
+            .authorized(|client| async move { client.find_by_url(url).await })
+            .await?;
+        if let Some(first) = found.first() {
+            let fullname = &first.name;
+            let duplicates = self
+                .authorized(|client| async move { client.duplicates(fullname).await })
+                .await?;
+            for duplicate in duplicates {
+                if !found.iter().any(|link| link.name == duplicate.name) {
+                    found.push(duplicate);
                 }
             }
-            Err(e)
         }
+
+        found.retain(|link| link.subreddit.eq_ignore_ascii_case(subreddit));
+        found.sort_by(|a, b| b.created_utc.total_cmp(&a.created_utc));
+        Ok(found)
+    }
+
+    /// Fail with a [`post_core::Error::Duplicate`] listing the earlier posts
+    /// if the link was already submitted to the subreddit.
+    pub async fn refuse_reposts(&self, subreddit: &str, link: &str) -> eyre::Result<()> {
+        let reposts = self.reposts(subreddit, link).await?;
+        if reposts.is_empty() {
+            return Ok(());
+        }
+        let listing: Vec<_> = reposts
+            .iter()
+            .map(|repost| {
+                format!(
+                    "{} points, {} — {}",
+                    repost.score,
+                    age(repost.created_utc),
+                    repost.permalink_url()
+                )
+            })
+            .collect();
+        Err(post_core::Error::Duplicate(format!(
+            "{link} was already posted to r/{subreddit}:\n  {}\n(pass --force to submit anyway)",
+            listing.join("\n  ")
+        ))
+        .into())
     }
-}
 
-/// Submit a link post to a subreddit.
-pub async fn post_link(subreddit: &str, title: &str, url: &str) -> eyre::Result<post_core::PostResult> {
-    let mut config = post_core::Config::load()?;
+    /// Reply to a post or comment, given as a URL or fullname.
+    pub async fn comment(&self, parent: &str, text: &str) -> eyre::Result<post_core::PostResult> {
+        let parent = &fullname(parent)?;
+        let comment = self
+            .authorized(|client| async move { client.comment(parent, text).await })
+            .await?;
+        Ok(post_core::PostResult {
+            url: format!("https://www.reddit.com{}", comment.permalink),
+            id: comment.name,
+        })
+    }
+
+    /// Replace the text of a self post or comment, given as a URL or fullname.
+    pub async fn edit(&self, target: &str, text: &str) -> eyre::Result<()> {
+        let target = &fullname(target)?;
+        self.authorized(|client| async move { client.edit(target, text).await })
+            .await
+    }
+
+    /// Crosspost an existing post, given as a URL or ID, to another
+    /// subreddit. The title defaults to the original post's.
+    pub async fn crosspost(
+        &self,
+        source: &str,
+        subreddit: &str,
+        title: Option<&str>,
+    ) -> eyre::Result<post_core::PostResult> {
+        let source = &post_fullname(source)?;
+        let title = match title {
+            Some(title) => title.to_string(),
+            None => {
+                self.authorized(|client| async move { client.info(&[source]).await })
+                    .await?
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| eyre::eyre!("post {source} not found"))?
+                    .title
+            }
+        };
+        let title = &title;
 
-    let reddit_config = config
-        .reddit
-        .as_ref()
-        .ok_or_else(|| eyre::eyre!("not authenticated with Reddit — run `post reddit auth` first"))?
-        .clone();
+        let submission = self
+            .authorized(|client| async move { client.crosspost(subreddit, title, source).await })
+            .await?;
+        Ok(post_core::PostResult {
+            id: submission.name,
+            url: submission.url,
+        })
+    }
+
+    /// Apply the options Reddit only accepts once the post exists. The post
+    /// is returned even if they fail, so it can still be reported and undone.
+    async fn finish(
+        &self,
+        result: post_core::PostResult,
+        subreddit: &str,
+        options: &SubmitOptions,
+    ) -> post_core::PostResult {
+        if options.original_content {
+            let fullname = &result.id;
+            if let Err(e) = self
+                .authorized(|client| async move {
+                    client.set_original_content(fullname, subreddit).await
+                })
+                .await
+            {
+                tracing::warn!("posted {} but failed to tag it as OC: {e:#}", result.url);
+            }
+        }
+        result
+    }
 
-    let client = Client::new(reddit_config.access_token.clone());
+    /// Run an API call with this account's credentials, refreshing the access
+    /// token and retrying once if it has expired.
+    async fn authorized<T, Op, Fut>(&self, op: Op) -> eyre::Result<T>
+    where
+        Op: Fn(Client) -> Fut,
+        Fut: Future<Output = Result<T, post_core::Error>>,
+    {
+        let mut config = post_core::Config::load()?;
 
-    match client.submit_link_post(subreddit, title, url).await {
-        Ok(post_url) => Ok(post_core::PostResult { url: post_url }),
-        Err(e) => {
-            // Try token refresh
-            if let Some(refresh_token) = &reddit_config.refresh_token {
-                tracing::debug!("attempting token refresh");
+        let reddit_config = config
+            .reddit
+            .require(self.account.as_deref(), self)?
+            .clone();
 
-                if let Ok((new_access, new_refresh)) = auth::refresh(
+        post_core::with_refresh(
+            &mut config,
+            |config| {
+                config
+                    .reddit
+                    .get_mut(self.account.as_deref())
+                    .map(|reddit| &mut reddit.tokens)
+            },
+            |refresh_token| async move {
+                auth::refresh(
                     &reddit_config.client_id,
                     &reddit_config.client_secret,
-                    refresh_token,
+                    &refresh_token,
                 )
                 .await
-                {
-                    config.reddit = Some(post_core::RedditConfig {
-                        client_id: reddit_config.client_id,
-                        client_secret: reddit_config.client_secret,
-                        username: reddit_config.username,
-                        access_token: new_access.clone(),
-                        refresh_token: new_refresh,
-                    });
-                    config.save()?;
-
-                    let client = Client::new(new_access);
-                    let post_url = client.submit_link_post(subreddit, title, url).await?;
-                    return Ok(post_core::PostResult { url: post_url });
-                }
-            }
-            Err(e)
+            },
+            |access_token| op(Client::new(access_token)),
+        )
+        .await
+    }
+}
+
+/// How long ago a Unix timestamp was, like `3d ago`.
+fn age(timestamp: f64) -> String {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0.0, |elapsed| elapsed.as_secs_f64());
+    let secs = (now - timestamp).max(0.0) as u64;
+    match secs {
+        0..60 => "just now".to_string(),
+        60..3600 => format!("{}m ago", secs / 60),
+        3600..86400 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86400),
+    }
+}
+
+#[async_trait::async_trait]
+impl post_core::Platform for Reddit {
+    fn name(&self) -> &'static str {
+        "reddit"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Reddit"
+    }
+
+    fn capabilities(&self) -> post_core::Capabilities {
+        post_core::Capabilities {
+            max_text_len: Some(MAX_TITLE_LEN),
+            body: true,
+            link: true,
+            link_len: None,
+            community: true,
+        }
+    }
+
+    /// Authenticate with Reddit. With a password this uses the password grant
+    /// flow (for "script" apps), otherwise the authorization code flow in a
+    /// browser (for "web app" and "installed app" types).
+    async fn authenticate(&self, credentials: &post_core::Credentials) -> eyre::Result<()> {
+        if let Some(name) = &self.account {
+            post_core::check_account_name(name)?;
+        }
+        let (tokens, username) = if credentials.browser || credentials.no_browser {
+            let callback = credentials
+                .callback
+                .clone()
+                .or(&post_core::Config::load()?.callback);
+            let tokens = auth::authorize(
+                &credentials.client_id,
+                &credentials.client_secret,
+                &callback,
+                credentials.no_browser,
+                &credentials.scopes,
+            )
+            .await?;
+            let account = Client::new(tokens.access_token.clone())
+                .me()
+                .await
+                .wrap_err("failed to look up the Reddit username")?;
+            (tokens, account.name)
+        } else {
+            let (Some(username), Some(password)) = (&credentials.username, &credentials.password)
+            else {
+                eyre::bail!("Reddit authentication requires a username and password");
+            };
+            let tokens = auth::authenticate(
+                &credentials.client_id,
+                &credentials.client_secret,
+                username,
+                password,
+                credentials.otp.as_deref(),
+            )
+            .await?;
+            (tokens, username.clone())
+        };
+
+        // Load after logging in so accounts saved meanwhile are kept
+        let mut config = post_core::Config::load()?;
+        let name = self
+            .account
+            .as_deref()
+            .or_else(|| config.reddit.resolve(None))
+            .unwrap_or(post_core::DEFAULT_ACCOUNT)
+            .to_string();
+        config.reddit.insert(
+            name,
+            post_core::RedditConfig {
+                client_id: credentials.client_id.clone(),
+                client_secret: credentials.client_secret.clone(),
+                username,
+                tokens,
+            },
+        );
+        config.save()
+    }
+
+    async fn publish(&self, content: &post_core::Content) -> eyre::Result<post_core::PostResult> {
+        let subreddit = content
+            .community
+            .as_deref()
+            .ok_or_else(|| eyre::eyre!("Reddit posts require a subreddit"))?;
+        if !content.skip_checks {
+            self.check(&Draft {
+                subreddit,
+                title: &content.text,
+                kind: content
+                    .link
+                    .as_deref()
+                    .map_or_else(|| DraftKind::Text(content.body.as_deref()), DraftKind::Link),
+                flair: false,
+            })
+            .await?;
+        }
+        if let Some(link) = &content.link
+            && !content.force
+        {
+            self.refuse_reposts(subreddit, link).await?;
         }
+        let options = SubmitOptions {
+            resubmit: content.force,
+            ..Default::default()
+        };
+        let result = self.submit(content, &options).await?;
+
+        // Link posts have no body, so post it as the first comment instead.
+        // The post exists either way, so a failed comment doesn't fail the publish.
+        if let (Some(_), Some(body)) = (&content.link, &content.body)
+            && let Err(e) = self.comment(&result.id, body).await
+        {
+            tracing::warn!(
+                "posted {} but failed to add the body as a comment: {e:#}",
+                result.url
+            );
+        }
+        Ok(result)
+    }
+
+    /// Delete a post or comment, given as a URL or fullname.
+    async fn delete(&self, id: &str) -> eyre::Result<()> {
+        let id = &fullname(id)?;
+        self.authorized(|client| async move { client.delete(id).await })
+            .await
+    }
+
+    fn account(&self) -> Option<String> {
+        let config = post_core::Config::load().ok()?;
+        config
+            .reddit
+            .resolve(self.account.as_deref())
+            .map(str::to_string)
     }
+
+    fn status(&self) -> eyre::Result<Option<String>> {
+        let config = post_core::Config::load()?;
+        let account = self.account.as_deref();
+        let Some((name, reddit)) = config
+            .reddit
+            .resolve(account)
+            .zip(config.reddit.get(account))
+        else {
+            return Ok(None);
+        };
+        Ok(Some(match reddit.tokens.lifetime() {
+            Some(lifetime) => format!(
+                "Authenticated with Reddit as u/{} (account `{name}`, {lifetime})",
+                reddit.username
+            ),
+            None => format!(
+                "Authenticated with Reddit as u/{} (account `{name}`)",
+                reddit.username
+            ),
+        }))
+    }
+
+    fn logout(&self) -> eyre::Result<()> {
+        let mut config = post_core::Config::load()?;
+        config.reddit.remove(self.account.as_deref());
+        config.save()
+    }
+}
+
+/// Authenticate with Reddit using password grant flow (for "script" apps).
+#[deprecated(note = "use `Reddit::authenticate` through `post_core::Platform`")]
+pub async fn auth(
+    client_id: &str,
+    client_secret: &str,
+    username: &str,
+    password: &str,
+) -> eyre::Result<post_core::Config> {
+    use post_core::Platform as _;
+
+    let credentials = post_core::Credentials {
+        client_id: client_id.to_string(),
+        client_secret: client_secret.to_string(),
+        username: Some(username.to_string()),
+        password: Some(password.to_string()),
+        ..Default::default()
+    };
+    Reddit::default().authenticate(&credentials).await?;
+    post_core::Config::load()
+}
+
+/// Submit a text post to a subreddit.
+#[deprecated(note = "use `Reddit::submit`, or `Reddit::publish` through `post_core::Platform`")]
+pub async fn post(
+    subreddit: &str,
+    title: &str,
+    text: Option<&str>,
+) -> eyre::Result<post_core::PostResult> {
+    let content = post_core::Content {
+        text: title.to_string(),
+        body: text.map(str::to_string),
+        community: Some(subreddit.to_string()),
+        ..Default::default()
+    };
+    Reddit::default()
+        .submit(&content, &SubmitOptions::default())
+        .await
+}
+
+/// Submit a link post to a subreddit.
+#[deprecated(note = "use `Reddit::submit`, or `Reddit::publish` through `post_core::Platform`")]
+pub async fn post_link(
+    subreddit: &str,
+    title: &str,
+    url: &str,
+) -> eyre::Result<post_core::PostResult> {
+    let content = post_core::Content {
+        text: title.to_string(),
+        link: Some(url.to_string()),
+        community: Some(subreddit.to_string()),
+        ..Default::default()
+    };
+    Reddit::default()
+        .submit(&content, &SubmitOptions::default())
+        .await
 }
 
 /// Check authentication status
+#[deprecated(note = "use `Reddit::status` through `post_core::Platform`")]
 pub fn status() -> Option<String> {
-    let config = post_core::Config::load().ok()?;
-    let reddit = config.reddit.as_ref()?;
-    Some(format!("Authenticated with Reddit as u/{}", reddit.username))
+    post_core::Platform::status(&Reddit::default())
+        .ok()
+        .flatten()
 }
 
 /// Clear Reddit credentials
+#[deprecated(note = "use `Reddit::logout` through `post_core::Platform`")]
 pub fn logout() -> eyre::Result<()> {
-    let mut config = post_core::Config::load()?;
-    config.reddit = None;
-    config.save()?;
-    Ok(())
+    post_core::Platform::logout(&Reddit::default())
 }
@@ -0,0 +1,202 @@
+use eyre::WrapErr as _;
+
+/// Most images allowed in one gallery
+const MAX_GALLERY_ITEMS: usize = 20;
+/// Give up looking for a processed media post after this many checks
+const MAX_SUBMITTED_CHECKS: u32 = 30;
+/// Time between checks of the user's submissions
+const SUBMITTED_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// An image to post, with optional gallery caption and link
+pub struct MediaFile {
+    pub path: std::path::PathBuf,
+    pub caption: Option<String>,
+    pub outbound_url: Option<String>,
+}
+
+impl MediaFile {
+    /// Whether images go up as a gallery rather than an image post: several
+    /// images, or one with a caption or link, which only galleries carry
+    pub fn is_gallery(images: &[Self]) -> bool {
+        match images {
+            [image] => image.caption.is_some() || image.outbound_url.is_some(),
+            _ => images.len() > 1,
+        }
+    }
+}
+
+impl super::Reddit {
+    /// Post images. A single image without a caption or link becomes an image
+    /// post; anything else becomes a gallery.
+    pub async fn submit_images(
+        &self,
+        subreddit: &str,
+        title: &str,
+        images: &[MediaFile],
+        options: &super::SubmitOptions,
+    ) -> eyre::Result<post_core::PostResult> {
+        if images.is_empty() {
+            eyre::bail!("at least one image is required");
+        }
+        if images.len() > MAX_GALLERY_ITEMS {
+            eyre::bail!("a gallery can have at most {MAX_GALLERY_ITEMS} images");
+        }
+        for image in images {
+            if !mime_type(&image.path)?.starts_with("image/") {
+                eyre::bail!("{} is not an image", image.path.display());
+            }
+        }
+
+        if let [image] = images
+            && !MediaFile::is_gallery(images)
+        {
+            let uploaded = self.upload(&image.path).await?;
+            let url = &uploaded.url;
+            self.authorized(|client| async move {
+                client
+                    .submit_image_post(subreddit, title, url, options)
+                    .await
+            })
+            .await?;
+            let result = self.find_submitted(subreddit, title).await?;
+            return Ok(self.finish(result, subreddit, options).await);
+        }
+
+        let mut items = Vec::new();
+        for image in images {
+            let uploaded = self.upload(&image.path).await?;
+            items.push(super::GalleryItem {
+                media_id: uploaded.asset_id,
+                caption: image.caption.clone().unwrap_or_default(),
+                outbound_url: image.outbound_url.clone().unwrap_or_default(),
+            });
+        }
+        let items = &items;
+        let submission = self
+            .authorized(|client| async move {
+                client
+                    .submit_gallery_post(subreddit, title, items, options)
+                    .await
+            })
+            .await?;
+
+        let result = post_core::PostResult {
+            id: submission.name,
+            url: submission.url,
+        };
+        Ok(self.finish(result, subreddit, options).await)
+    }
+
+    /// Post a video with the poster image shown before playback.
+    pub async fn submit_video(
+        &self,
+        subreddit: &str,
+        title: &str,
+        video: &std::path::Path,
+        poster: &std::path::Path,
+        options: &super::SubmitOptions,
+    ) -> eyre::Result<post_core::PostResult> {
+        if !mime_type(video)?.starts_with("video/") {
+            eyre::bail!("{} is not a video", video.display());
+        }
+        if !mime_type(poster)?.starts_with("image/") {
+            eyre::bail!("poster {} is not an image", poster.display());
+        }
+
+        let video = self.upload(video).await?;
+        let poster = self.upload(poster).await?;
+        let (video_url, poster_url) = (&video.url, &poster.url);
+        self.authorized(|client| async move {
+            client
+                .submit_video_post(subreddit, title, video_url, poster_url, options)
+                .await
+        })
+        .await?;
+        let result = self.find_submitted(subreddit, title).await?;
+        Ok(self.finish(result, subreddit, options).await)
+    }
+
+    async fn upload(&self, path: &std::path::Path) -> eyre::Result<super::UploadedMedia> {
+        let mime_type = mime_type(path)?;
+        let data =
+            std::fs::read(path).wrap_err_with(|| format!("failed to read {}", path.display()))?;
+        let filename = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("upload");
+        let data = &data;
+
+        let uploaded = self
+            .authorized(
+                |client| async move { client.upload_media(filename, mime_type, data).await },
+            )
+            .await
+            .wrap_err_with(|| format!("failed to upload {}", path.display()))?;
+        tracing::debug!("uploaded {} to {}", path.display(), uploaded.url);
+        Ok(uploaded)
+    }
+
+    /// Media posts are created after Reddit finishes processing the upload,
+    /// so look for the new post among the account's recent submissions.
+    async fn find_submitted(
+        &self,
+        subreddit: &str,
+        title: &str,
+    ) -> eyre::Result<post_core::PostResult> {
+        let config = post_core::Config::load()?;
+        let username = config
+            .reddit
+            .require(self.account.as_deref(), self)?
+            .username
+            .clone();
+        let username = &username;
+        let submitted_after = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0.0, |elapsed| elapsed.as_secs_f64())
+            // Allow for clock skew between this machine and Reddit
+            - 300.0;
+
+        for _ in 0..MAX_SUBMITTED_CHECKS {
+            let links = self
+                .authorized(|client| async move { client.submitted(username, 5).await })
+                .await?;
+            if let Some(link) = links.into_iter().find(|link| {
+                link.title == title
+                    && link.subreddit.eq_ignore_ascii_case(subreddit)
+                    && link.created_utc >= submitted_after
+            }) {
+                return Ok(post_core::PostResult {
+                    url: link.permalink_url(),
+                    id: link.name,
+                });
+            }
+            tracing::debug!("waiting for Reddit to finish processing the post");
+            tokio::time::sleep(SUBMITTED_CHECK_INTERVAL).await;
+        }
+
+        eyre::bail!(
+            "Reddit accepted the post but it has not appeared yet — check https://www.reddit.com/user/{username}/submitted"
+        )
+    }
+}
+
+/// MIME type from the file extension
+fn mime_type(path: &std::path::Path) -> eyre::Result<&'static str> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+
+    Ok(match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "mp4" => "video/mp4",
+        "mov" => "video/quicktime",
+        _ => eyre::bail!(
+            "unsupported media type for {} (expected png, jpg, gif, mp4 or mov)",
+            path.display()
+        ),
+    })
+}
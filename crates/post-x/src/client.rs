@@ -1,15 +1,82 @@
-use eyre::WrapErr as _;
-
 const TWEETS_URL: &str = "https://api.x.com/2/tweets";
+const MEDIA_UPLOAD_URL: &str = "https://api.x.com/2/media/upload";
+const MEDIA_METADATA_URL: &str = "https://api.x.com/2/media/metadata";
+/// Size of each APPEND segment in chunked uploads
+const CHUNK_SIZE: usize = 4 * 1024 * 1024;
+/// Give up waiting for video processing after this many STATUS checks
+const MAX_STATUS_CHECKS: u32 = 120;
 
 pub struct Client {
     access_token: String,
     http: reqwest::Client,
 }
 
+/// A tweet to create
+#[derive(serde::Serialize, Default)]
+pub struct TweetRequest {
+    pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reply: Option<Reply>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media: Option<Media>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poll: Option<super::Poll>,
+    /// Tweet to quote
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quote_tweet_id: Option<String>,
+    /// Who may reply; everyone if `None`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reply_settings: Option<ReplySettings>,
+}
+
 #[derive(serde::Serialize)]
-struct TweetRequest<'a> {
-    text: &'a str,
+pub struct Reply {
+    pub in_reply_to_tweet_id: String,
+}
+
+/// Who may reply to a tweet
+#[derive(serde::Serialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub enum ReplySettings {
+    /// Accounts the author follows
+    Following,
+    /// Accounts mentioned in the tweet
+    MentionedUsers,
+}
+
+impl std::str::FromStr for ReplySettings {
+    type Err = eyre::Report;
+
+    fn from_str(value: &str) -> eyre::Result<Self> {
+        match value {
+            "following" => Ok(Self::Following),
+            "mentionedUsers" => Ok(Self::MentionedUsers),
+            _ => eyre::bail!("expected `following` or `mentionedUsers`, got `{value}`"),
+        }
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct Media {
+    pub media_ids: Vec<String>,
+}
+
+/// Kind of media being uploaded, which decides the upload flow
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MediaCategory {
+    Image,
+    Gif,
+    Video,
+}
+
+impl MediaCategory {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Image => "tweet_image",
+            Self::Gif => "tweet_gif",
+            Self::Video => "tweet_video",
+        }
+    }
 }
 
 #[derive(serde::Deserialize)]
@@ -23,6 +90,47 @@ pub struct TweetData {
     pub text: String,
 }
 
+#[derive(serde::Deserialize)]
+struct DeleteResponse {
+    data: DeleteData,
+}
+
+#[derive(serde::Deserialize)]
+struct DeleteData {
+    deleted: bool,
+}
+
+#[derive(serde::Deserialize)]
+struct MediaResponse {
+    data: MediaData,
+}
+
+#[derive(serde::Deserialize)]
+struct MediaData {
+    id: String,
+    processing_info: Option<ProcessingInfo>,
+}
+
+/// Progress of server-side processing for GIFs and videos
+#[derive(serde::Deserialize)]
+struct ProcessingInfo {
+    state: String,
+    check_after_secs: Option<u64>,
+    error: Option<ProcessingError>,
+}
+
+#[derive(serde::Deserialize)]
+struct ProcessingError {
+    message: Option<String>,
+}
+
+/// Problem details returned by the X API on failure
+#[derive(serde::Deserialize)]
+struct ErrorResponse {
+    title: Option<String>,
+    detail: Option<String>,
+}
+
 impl Client {
     pub fn new(access_token: String) -> Self {
         Self {
@@ -31,28 +139,200 @@ impl Client {
         }
     }
 
-    pub async fn post_tweet(&self, text: &str) -> eyre::Result<TweetResponse> {
-        let request = TweetRequest { text };
+    pub async fn post_tweet(
+        &self,
+        request: &TweetRequest,
+    ) -> Result<TweetResponse, post_core::Error> {
+        let response = self
+            .send(
+                self.http
+                    .post(TWEETS_URL)
+                    .header("Content-Type", "application/json")
+                    .json(request),
+            )
+            .await?;
+
+        response.json().await.map_err(post_core::Error::decode)
+    }
 
+    /// Delete one of the authenticated user's tweets.
+    pub async fn delete_tweet(&self, id: &str) -> Result<(), post_core::Error> {
+        let response: DeleteResponse = self
+            .send(self.http.delete(format!("{TWEETS_URL}/{id}")))
+            .await?
+            .json()
+            .await
+            .map_err(post_core::Error::decode)?;
+        if !response.data.deleted {
+            return Err(post_core::Error::PlatformError {
+                code: "NOT_DELETED".to_string(),
+                message: format!("X did not delete tweet {id}"),
+            });
+        }
+        Ok(())
+    }
+
+    /// Upload media and return its media ID.
+    ///
+    /// Images are sent in a single request. GIFs and videos go through the
+    /// chunked INIT/APPEND/FINALIZE flow, then wait for processing to finish.
+    pub async fn upload_media(
+        &self,
+        data: &[u8],
+        media_type: &str,
+        category: MediaCategory,
+    ) -> Result<String, post_core::Error> {
+        if category == MediaCategory::Image {
+            let media = reqwest::multipart::Part::bytes(data.to_vec())
+                .mime_str(media_type)
+                .map_err(|_| {
+                    post_core::Error::Validation(format!("unsupported media type {media_type}"))
+                })?;
+            let form = reqwest::multipart::Form::new()
+                .text("media_category", category.as_str())
+                .part("media", media);
+            let response = self
+                .send(self.http.post(MEDIA_UPLOAD_URL).multipart(form))
+                .await?;
+            let upload: MediaResponse = response.json().await.map_err(post_core::Error::decode)?;
+            return Ok(upload.data.id);
+        }
+
+        let total_bytes = data.len().to_string();
+        let init = [
+            ("command", "INIT"),
+            ("total_bytes", &total_bytes),
+            ("media_type", media_type),
+            ("media_category", category.as_str()),
+        ];
         let response = self
-            .http
-            .post(TWEETS_URL)
+            .send(self.http.post(MEDIA_UPLOAD_URL).form(&init))
+            .await?;
+        let init: MediaResponse = response.json().await.map_err(post_core::Error::decode)?;
+        let media_id = init.data.id;
+
+        for (index, chunk) in data.chunks(CHUNK_SIZE).enumerate() {
+            let form = reqwest::multipart::Form::new()
+                .text("command", "APPEND")
+                .text("media_id", media_id.clone())
+                .text("segment_index", index.to_string())
+                .part("media", reqwest::multipart::Part::bytes(chunk.to_vec()));
+            self.send(self.http.post(MEDIA_UPLOAD_URL).multipart(form))
+                .await?;
+        }
+
+        let finalize = [("command", "FINALIZE"), ("media_id", &media_id)];
+        let response = self
+            .send(self.http.post(MEDIA_UPLOAD_URL).form(&finalize))
+            .await?;
+        let finalize: MediaResponse = response.json().await.map_err(post_core::Error::decode)?;
+
+        self.wait_for_processing(&media_id, finalize.data.processing_info)
+            .await?;
+        Ok(media_id)
+    }
+
+    /// Poll STATUS until processing succeeds or fails.
+    async fn wait_for_processing(
+        &self,
+        media_id: &str,
+        mut info: Option<ProcessingInfo>,
+    ) -> Result<(), post_core::Error> {
+        for _ in 0..MAX_STATUS_CHECKS {
+            let Some(current) = info else {
+                return Ok(());
+            };
+            match current.state.as_str() {
+                "succeeded" => return Ok(()),
+                "failed" => {
+                    return Err(post_core::Error::Validation(
+                        current
+                            .error
+                            .and_then(|error| error.message)
+                            .unwrap_or_else(|| "media processing failed".to_string()),
+                    ));
+                }
+                _ => {}
+            }
+
+            let wait = current.check_after_secs.unwrap_or(1);
+            tracing::debug!(
+                "media {media_id} is {}, checking again in {wait}s",
+                current.state
+            );
+            tokio::time::sleep(std::time::Duration::from_secs(wait)).await;
+
+            let status = [("command", "STATUS"), ("media_id", media_id)];
+            let response = self
+                .send(self.http.get(MEDIA_UPLOAD_URL).query(&status))
+                .await?;
+            let status: MediaResponse = response.json().await.map_err(post_core::Error::decode)?;
+            info = status.data.processing_info;
+        }
+
+        Err(post_core::Error::PlatformError {
+            code: "processing_timeout".to_string(),
+            message: format!("media {media_id} is still processing"),
+        })
+    }
+
+    /// Set the alt text shown for uploaded media.
+    pub async fn set_alt_text(&self, media_id: &str, alt: &str) -> Result<(), post_core::Error> {
+        let metadata = serde_json::json!({
+            "id": media_id,
+            "metadata": { "alt_text": { "text": alt } },
+        });
+        self.send(self.http.post(MEDIA_METADATA_URL).json(&metadata))
+            .await?;
+        Ok(())
+    }
+
+    /// Send an authenticated request, turning failures into typed errors.
+    async fn send(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, post_core::Error> {
+        let response = request
             .header("Authorization", format!("Bearer {}", self.access_token))
-            .header("Content-Type", "application/json")
-            .json(&request)
             .send()
             .await
-            .wrap_err("failed to send tweet request")?;
+            .map_err(post_core::Error::network)?;
 
         if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            eyre::bail!("tweet failed ({status}): {body}");
+            return Err(error_from_response(response).await);
         }
 
-        response
-            .json()
-            .await
-            .wrap_err("failed to parse tweet response")
+        Ok(response)
+    }
+}
+
+/// Classify a failed X API response.
+async fn error_from_response(response: reqwest::Response) -> post_core::Error {
+    let status = response.status();
+    let reset = response
+        .headers()
+        .get("x-rate-limit-reset")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok())
+        .map(|secs| std::time::UNIX_EPOCH + std::time::Duration::from_secs(secs));
+    let body = response.text().await.unwrap_or_default();
+
+    let message = serde_json::from_str::<ErrorResponse>(&body)
+        .ok()
+        .and_then(|error| error.detail.or(error.title))
+        .unwrap_or(body);
+
+    match status {
+        reqwest::StatusCode::UNAUTHORIZED => post_core::Error::Unauthorized(message),
+        reqwest::StatusCode::TOO_MANY_REQUESTS => post_core::Error::RateLimited { reset },
+        reqwest::StatusCode::FORBIDDEN if message.contains("duplicate content") => {
+            post_core::Error::Duplicate(message)
+        }
+        reqwest::StatusCode::BAD_REQUEST => post_core::Error::Validation(message),
+        reqwest::StatusCode::NOT_FOUND => post_core::Error::NotFound(message),
+        _ => post_core::Error::PlatformError {
+            code: status.as_u16().to_string(),
+            message,
+        },
     }
 }
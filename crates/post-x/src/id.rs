@@ -0,0 +1,73 @@
+/// Turn a tweet URL or ID into a tweet ID.
+///
+/// Accepts bare IDs and links like `https://x.com/user/status/123` or
+/// `https://twitter.com/i/web/status/123`.
+pub fn tweet_id(input: &str) -> eyre::Result<String> {
+    let input = input.trim();
+    if is_id(input) {
+        return Ok(input.to_string());
+    }
+
+    let url = reqwest::Url::parse(input)
+        .map_err(|_| eyre::eyre!("`{input}` is not a tweet URL or ID"))?;
+    let host = url
+        .host_str()
+        .unwrap_or_default()
+        .trim_start_matches("www.")
+        .trim_start_matches("mobile.");
+    if host == "x.com" || host == "twitter.com" {
+        let mut segments = url.path_segments().into_iter().flatten();
+        if segments.any(|segment| segment == "status")
+            && let Some(id) = segments.next()
+            && is_id(id)
+        {
+            return Ok(id.to_string());
+        }
+    }
+
+    eyre::bail!("`{input}` is not a link to a tweet")
+}
+
+fn is_id(value: &str) -> bool {
+    !value.is_empty() && value.chars().all(|c| c.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::tweet_id;
+
+    #[test]
+    fn parses_ids_and_links() {
+        let cases = [
+            ("1234567890", "1234567890"),
+            (" 1234567890\n", "1234567890"),
+            ("https://x.com/user/status/1234567890", "1234567890"),
+            ("https://twitter.com/user/status/1234567890", "1234567890"),
+            ("https://www.x.com/user/status/1234567890", "1234567890"),
+            (
+                "https://mobile.twitter.com/user/status/1234567890",
+                "1234567890",
+            ),
+            ("https://x.com/i/web/status/1234567890", "1234567890"),
+            ("https://x.com/user/status/1234567890/photo/1", "1234567890"),
+            ("https://x.com/user/status/1234567890?s=20", "1234567890"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(tweet_id(input).unwrap(), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn rejects_other_input() {
+        for input in [
+            "",
+            "12ab",
+            "https://x.com/user",
+            "https://x.com/user/status/",
+            "https://x.com/user/status/abc",
+            "https://example.com/user/status/1234567890",
+        ] {
+            assert!(tweet_id(input).is_err(), "{input:?}");
+        }
+    }
+}
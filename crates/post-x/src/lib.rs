@@ -1,87 +1,215 @@
 mod auth;
 mod client;
+mod id;
+mod media;
+mod poll;
+mod thread;
 
 pub use auth::OAuth2Client;
-pub use client::Client;
+pub use client::{Client, Media, MediaCategory, Reply, ReplySettings, TweetRequest};
+pub use id::tweet_id;
+pub use media::MediaFile;
+pub use poll::{Poll, poll_duration};
+pub use thread::{ThreadError, split_thread};
 
-const CALLBACK_PORT: u16 = 8080;
+const MAX_TWEET_LEN: usize = 280;
+/// Length X counts for any link, which it shortens to a t.co URL
+const TCO_URL_LEN: usize = 23;
 
+/// Redirect URI to register with the X app when the callback isn't configured
 pub fn redirect_uri() -> String {
-    format!("http://localhost:{CALLBACK_PORT}/callback")
+    post_core::CallbackConfig::default()
+        .redirect_uri()
+        .unwrap_or_default()
+}
+
+/// X (Twitter), posting as one of the saved accounts
+#[derive(Default)]
+pub struct X {
+    /// Saved account to use, or the default account if `None`
+    account: Option<String>,
+}
+
+impl X {
+    pub fn new(account: Option<String>) -> Self {
+        Self { account }
+    }
+
+    /// Post a single tweet.
+    pub async fn tweet(&self, request: &TweetRequest) -> eyre::Result<post_core::PostResult> {
+        let response = self
+            .authorized(|client| async move { client.post_tweet(request).await })
+            .await?;
+
+        Ok(post_core::PostResult {
+            url: format!("https://x.com/i/status/{}", response.data.id),
+            id: response.data.id,
+        })
+    }
+
+    /// Run an API call with this account's credentials, refreshing the access
+    /// token and retrying once if it has expired.
+    async fn authorized<T, Op, Fut>(&self, op: Op) -> eyre::Result<T>
+    where
+        Op: Fn(Client) -> Fut,
+        Fut: Future<Output = Result<T, post_core::Error>>,
+    {
+        let mut config = post_core::Config::load()?;
+
+        let x_config = config.x.require(self.account.as_deref(), self)?;
+        let oauth = OAuth2Client::new(x_config.client_id.clone(), x_config.client_secret.clone());
+
+        post_core::with_refresh(
+            &mut config,
+            |config| {
+                config
+                    .x
+                    .get_mut(self.account.as_deref())
+                    .map(|x| &mut x.tokens)
+            },
+            |refresh_token| async move { oauth.refresh(&refresh_token).await },
+            |access_token| op(Client::new(access_token)),
+        )
+        .await
+    }
+}
+
+#[async_trait::async_trait]
+impl post_core::Platform for X {
+    fn name(&self) -> &'static str {
+        "x"
+    }
+
+    fn display_name(&self) -> &'static str {
+        "X"
+    }
+
+    fn capabilities(&self) -> post_core::Capabilities {
+        post_core::Capabilities {
+            max_text_len: Some(MAX_TWEET_LEN),
+            body: false,
+            link: true,
+            link_len: Some(TCO_URL_LEN),
+            community: false,
+        }
+    }
+
+    /// Authenticate with X/Twitter using OAuth2 PKCE flow.
+    async fn authenticate(&self, credentials: &post_core::Credentials) -> eyre::Result<()> {
+        if let Some(name) = &self.account {
+            post_core::check_account_name(name)?;
+        }
+        let oauth = OAuth2Client::new(
+            credentials.client_id.clone(),
+            credentials.client_secret.clone(),
+        );
+        let callback = credentials
+            .callback
+            .clone()
+            .or(&post_core::Config::load()?.callback);
+        let tokens = oauth.authorize(&callback, credentials.no_browser).await?;
+
+        // Load after the browser round trip so accounts saved meanwhile are kept
+        let mut config = post_core::Config::load()?;
+        let name = self
+            .account
+            .as_deref()
+            .or_else(|| config.x.resolve(None))
+            .unwrap_or(post_core::DEFAULT_ACCOUNT)
+            .to_string();
+        config.x.insert(
+            name,
+            post_core::XConfig {
+                client_id: credentials.client_id.clone(),
+                client_secret: credentials.client_secret.clone(),
+                tokens,
+            },
+        );
+        config.save()
+    }
+
+    /// Post a tweet, appending the link if one is given.
+    async fn publish(&self, content: &post_core::Content) -> eyre::Result<post_core::PostResult> {
+        let text = match &content.link {
+            Some(link) => format!("{}\n\n{link}", content.text),
+            None => content.text.clone(),
+        };
+
+        self.tweet(&TweetRequest {
+            text,
+            ..Default::default()
+        })
+        .await
+    }
+
+    /// Delete a tweet, given as a URL or ID.
+    async fn delete(&self, id: &str) -> eyre::Result<()> {
+        let id = &tweet_id(id)?;
+        self.authorized(|client| async move { client.delete_tweet(id).await })
+            .await
+    }
+
+    fn account(&self) -> Option<String> {
+        let config = post_core::Config::load().ok()?;
+        config
+            .x
+            .resolve(self.account.as_deref())
+            .map(str::to_string)
+    }
+
+    fn status(&self) -> eyre::Result<Option<String>> {
+        let config = post_core::Config::load()?;
+        let account = self.account.as_deref();
+        let Some((name, x)) = config.x.resolve(account).zip(config.x.get(account)) else {
+            return Ok(None);
+        };
+        Ok(Some(match x.tokens.lifetime() {
+            Some(lifetime) => format!("Authenticated with X (account `{name}`, {lifetime})"),
+            None => format!("Authenticated with X (account `{name}`)"),
+        }))
+    }
+
+    fn logout(&self) -> eyre::Result<()> {
+        let mut config = post_core::Config::load()?;
+        config.x.remove(self.account.as_deref());
+        config.save()
+    }
 }
 
 /// Authenticate with X/Twitter using OAuth2 PKCE flow.
+#[deprecated(note = "use `X::authenticate` through `post_core::Platform`")]
 pub async fn authenticate(client_id: &str, client_secret: &str) -> eyre::Result<post_core::Config> {
-    let oauth = OAuth2Client::new(client_id.to_string(), client_secret.to_string());
-    let (access_token, refresh_token) = oauth.authorize().await?;
+    use post_core::Platform as _;
 
-    let mut config = post_core::Config::load()?;
-    config.x = Some(post_core::XConfig {
+    let credentials = post_core::Credentials {
         client_id: client_id.to_string(),
         client_secret: client_secret.to_string(),
-        access_token,
-        refresh_token,
-    });
-    config.save()?;
-
-    Ok(config)
+        ..Default::default()
+    };
+    X::default().authenticate(&credentials).await?;
+    post_core::Config::load()
 }
 
 /// Post a tweet. Handles token refresh automatically.
+#[deprecated(note = "use `X::publish` through `post_core::Platform`, or `X::tweet`")]
 pub async fn post(text: &str) -> eyre::Result<post_core::PostResult> {
-    let mut config = post_core::Config::load()?;
+    use post_core::Platform as _;
 
-    let x_config = config
-        .x
-        .as_ref()
-        .ok_or_else(|| eyre::eyre!("not authenticated with X — run `post x auth` first"))?
-        .clone();
-
-    let client = Client::new(x_config.access_token.clone());
-
-    match client.post_tweet(text).await {
-        Ok(response) => Ok(post_core::PostResult {
-            url: format!("https://x.com/i/status/{}", response.data.id),
-        }),
-        Err(e) => {
-            // Try token refresh
-            if let Some(refresh_token) = &x_config.refresh_token {
-                tracing::debug!("attempting token refresh");
-                let oauth =
-                    OAuth2Client::new(x_config.client_id.clone(), x_config.client_secret.clone());
-
-                if let Ok((new_access, new_refresh)) = oauth.refresh(refresh_token).await {
-                    config.x = Some(post_core::XConfig {
-                        client_id: x_config.client_id,
-                        client_secret: x_config.client_secret,
-                        access_token: new_access.clone(),
-                        refresh_token: new_refresh,
-                    });
-                    config.save()?;
-
-                    let client = Client::new(new_access);
-                    let response = client.post_tweet(text).await?;
-                    return Ok(post_core::PostResult {
-                        url: format!("https://x.com/i/status/{}", response.data.id),
-                    });
-                }
-            }
-            Err(e)
-        }
-    }
+    let content = post_core::Content {
+        text: text.to_string(),
+        ..Default::default()
+    };
+    X::default().publish(&content).await
 }
 
 /// Check authentication status
+#[deprecated(note = "use `X::status` through `post_core::Platform`")]
 pub fn status() -> Option<String> {
-    let config = post_core::Config::load().ok()?;
-    config.x.as_ref()?;
-    Some("Authenticated with X".to_string())
+    post_core::Platform::status(&X::default()).ok().flatten()
 }
 
 /// Clear X credentials
+#[deprecated(note = "use `X::logout` through `post_core::Platform`")]
 pub fn logout() -> eyre::Result<()> {
-    let mut config = post_core::Config::load()?;
-    config.x = None;
-    config.save()?;
-    Ok(())
+    post_core::Platform::logout(&X::default())
 }
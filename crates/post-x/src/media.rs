@@ -0,0 +1,97 @@
+use eyre::WrapErr as _;
+
+/// Most attachments allowed on one tweet
+const MAX_MEDIA: usize = 4;
+/// Longest alt text X accepts, in characters
+const MAX_ALT_LEN: usize = 1000;
+
+/// A file to attach to a tweet
+pub struct MediaFile {
+    pub path: std::path::PathBuf,
+    /// Alt text describing the media
+    pub alt: Option<String>,
+}
+
+impl super::X {
+    /// Upload files to attach to a tweet, returning their media IDs in order.
+    ///
+    /// A tweet takes up to four images, or a single GIF or video.
+    pub async fn upload_media(&self, files: &[MediaFile]) -> eyre::Result<Vec<String>> {
+        let kinds = files
+            .iter()
+            .map(|file| media_type(&file.path))
+            .collect::<eyre::Result<Vec<_>>>()?;
+
+        if files.len() > MAX_MEDIA {
+            eyre::bail!("a tweet can have at most {MAX_MEDIA} media attachments");
+        }
+        if files.len() > 1
+            && kinds
+                .iter()
+                .any(|(_, category)| *category != super::MediaCategory::Image)
+        {
+            eyre::bail!("a GIF or video must be the only attachment on a tweet");
+        }
+        for file in files {
+            if let Some(alt) = &file.alt {
+                let len = alt.chars().count();
+                if len > MAX_ALT_LEN {
+                    return Err(post_core::Error::Validation(format!(
+                        "alt text for {} is {len} characters, the limit is {MAX_ALT_LEN}",
+                        file.path.display()
+                    ))
+                    .into());
+                }
+            }
+        }
+
+        let mut media_ids = Vec::new();
+        for (file, (media_type, category)) in files.iter().zip(kinds) {
+            let data = std::fs::read(&file.path)
+                .wrap_err_with(|| format!("failed to read {}", file.path.display()))?;
+            let data = &data;
+
+            let media_id = self
+                .authorized(|client| async move {
+                    client.upload_media(data, media_type, category).await
+                })
+                .await
+                .wrap_err_with(|| format!("failed to upload {}", file.path.display()))?;
+            tracing::debug!("uploaded {} as media {media_id}", file.path.display());
+
+            if let Some(alt) = &file.alt {
+                let media_id = &media_id;
+                self.authorized(|client| async move { client.set_alt_text(media_id, alt).await })
+                    .await
+                    .wrap_err_with(|| {
+                        format!("failed to set alt text for {}", file.path.display())
+                    })?;
+            }
+
+            media_ids.push(media_id);
+        }
+        Ok(media_ids)
+    }
+}
+
+/// MIME type and upload category, from the file extension
+fn media_type(path: &std::path::Path) -> eyre::Result<(&'static str, super::MediaCategory)> {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+
+    Ok(match extension.as_str() {
+        "png" => ("image/png", super::MediaCategory::Image),
+        "jpg" | "jpeg" => ("image/jpeg", super::MediaCategory::Image),
+        "webp" => ("image/webp", super::MediaCategory::Image),
+        "gif" => ("image/gif", super::MediaCategory::Gif),
+        "mp4" => ("video/mp4", super::MediaCategory::Video),
+        "mov" => ("video/quicktime", super::MediaCategory::Video),
+        _ => eyre::bail!(
+            "unsupported media type for {} (expected png, jpg, webp, gif, mp4 or mov)",
+            path.display()
+        ),
+    })
+}
@@ -0,0 +1,105 @@
+/// Fewest and most options a poll can have
+const MIN_OPTIONS: usize = 2;
+const MAX_OPTIONS: usize = 4;
+/// Longest option label, in characters
+const MAX_OPTION_LEN: usize = 25;
+/// Shortest and longest poll duration, in minutes
+const MIN_DURATION: u32 = 5;
+const MAX_DURATION: u32 = 7 * 24 * 60;
+
+/// A poll attached to a tweet
+#[derive(serde::Serialize)]
+pub struct Poll {
+    options: Vec<String>,
+    duration_minutes: u32,
+}
+
+impl Poll {
+    /// Build a poll, checking the option count, option length and duration
+    /// against X's limits.
+    pub fn new(options: Vec<String>, duration_minutes: u32) -> Result<Self, post_core::Error> {
+        if !(MIN_OPTIONS..=MAX_OPTIONS).contains(&options.len()) {
+            return Err(post_core::Error::Validation(format!(
+                "a poll needs {MIN_OPTIONS} to {MAX_OPTIONS} options, got {}",
+                options.len()
+            )));
+        }
+        if let Some(option) = options
+            .iter()
+            .find(|option| option.trim().is_empty() || option.chars().count() > MAX_OPTION_LEN)
+        {
+            return Err(post_core::Error::Validation(format!(
+                "poll option `{option}` must be 1 to {MAX_OPTION_LEN} characters"
+            )));
+        }
+        if !(MIN_DURATION..=MAX_DURATION).contains(&duration_minutes) {
+            return Err(post_core::Error::Validation(format!(
+                "a poll must run for {MIN_DURATION} minutes to 7 days, got {duration_minutes} minutes"
+            )));
+        }
+        Ok(Self {
+            options,
+            duration_minutes,
+        })
+    }
+}
+
+/// Parse a poll duration like `30m`, `12h` or `1d` into minutes. A bare
+/// number is taken as minutes.
+pub fn poll_duration(value: &str) -> eyre::Result<u32> {
+    let value = value.trim();
+    let (number, unit) = match value.char_indices().last() {
+        Some((index, unit)) if unit.is_ascii_alphabetic() => (&value[..index], unit),
+        _ => (value, 'm'),
+    };
+    let number: u32 = number
+        .parse()
+        .map_err(|_| eyre::eyre!("invalid duration `{value}` (expected e.g. 30m, 12h or 1d)"))?;
+    let minutes = match unit.to_ascii_lowercase() {
+        'm' => Some(number),
+        'h' => number.checked_mul(60),
+        'd' => number.checked_mul(24 * 60),
+        _ => eyre::bail!("invalid duration unit in `{value}` (expected m, h or d)"),
+    };
+    minutes.ok_or_else(|| eyre::eyre!("duration `{value}` is too long"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Poll, poll_duration};
+
+    #[test]
+    fn parses_durations() {
+        let cases = [
+            ("30", 30),
+            ("30m", 30),
+            ("12h", 720),
+            ("1d", 1440),
+            ("7D", 10080),
+            (" 5m ", 5),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(poll_duration(input).unwrap(), expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn rejects_bad_durations() {
+        for input in ["", "m", "1.5h", "-1d", "3w", "1 d", "99999999999d"] {
+            assert!(poll_duration(input).is_err(), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn validates_polls() {
+        let options = |options: &[&str]| options.iter().map(ToString::to_string).collect();
+        assert!(Poll::new(options(&["yes", "no"]), 1440).is_ok());
+        assert!(Poll::new(options(&["a", "b", "c", "d"]), 5).is_ok());
+        assert!(Poll::new(options(&["only"]), 1440).is_err());
+        assert!(Poll::new(options(&["a", "b", "c", "d", "e"]), 1440).is_err());
+        assert!(Poll::new(options(&["yes", " "]), 1440).is_err());
+        assert!(Poll::new(options(&["yes", &"x".repeat(26)]), 1440).is_err());
+        assert!(Poll::new(options(&["yes", "no"]), 4).is_err());
+        assert!(Poll::new(options(&["yes", "no"]), 7 * 24 * 60 + 1).is_err());
+    }
+}
@@ -0,0 +1,109 @@
+/// Line separating tweets in a thread file
+const SEPARATOR: &str = "---";
+
+/// A thread that stopped part way through
+pub struct ThreadError {
+    /// Tweets posted before the failure, in order
+    pub posted: Vec<post_core::PostResult>,
+    /// Index of the tweet that failed
+    pub failed: usize,
+    pub error: eyre::Report,
+}
+
+/// Split thread file contents into tweets on lines containing only `---`.
+pub fn split_thread(contents: &str) -> Vec<String> {
+    let mut tweets = vec![String::new()];
+    for line in contents.lines() {
+        if line.trim() == SEPARATOR {
+            tweets.push(String::new());
+        } else if let Some(tweet) = tweets.last_mut() {
+            tweet.push_str(line);
+            tweet.push('\n');
+        }
+    }
+    tweets
+        .into_iter()
+        .map(|tweet| tweet.trim().to_string())
+        .filter(|tweet| !tweet.is_empty())
+        .collect()
+}
+
+impl super::X {
+    /// Post a thread, each tweet replying to the previous one.
+    /// `reply_to` continues an existing thread from that tweet ID.
+    ///
+    /// Every tweet is checked against the length limit before anything is
+    /// posted. On failure the error says which tweet failed and which were
+    /// already posted, so the rest of the thread can be resumed.
+    pub async fn thread(
+        &self,
+        texts: &[String],
+        reply_to: Option<String>,
+    ) -> Result<Vec<post_core::PostResult>, ThreadError> {
+        let capabilities = post_core::Platform::capabilities(self);
+        for (failed, text) in texts.iter().enumerate() {
+            let tweet = post_core::Content {
+                text: text.clone(),
+                ..Default::default()
+            };
+            if let Err(error) = capabilities.check(&tweet) {
+                return Err(ThreadError {
+                    posted: Vec::new(),
+                    failed,
+                    error,
+                });
+            }
+        }
+
+        let mut posted: Vec<post_core::PostResult> = Vec::new();
+        let mut previous = reply_to;
+        for (index, text) in texts.iter().enumerate() {
+            let request = super::TweetRequest {
+                text: text.clone(),
+                reply: previous.take().map(|id| super::Reply {
+                    in_reply_to_tweet_id: id,
+                }),
+                ..Default::default()
+            };
+            match self.tweet(&request).await {
+                Ok(result) => {
+                    previous = Some(result.id.clone());
+                    posted.push(result);
+                }
+                Err(error) => {
+                    return Err(ThreadError {
+                        posted,
+                        failed: index,
+                        error,
+                    });
+                }
+            }
+        }
+        Ok(posted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::split_thread;
+
+    #[test]
+    fn splits_on_separator_lines() {
+        let cases: &[(&str, &[&str])] = &[
+            ("one\n---\ntwo\n---\nthree", &["one", "two", "three"]),
+            (
+                "  first line\nsecond line\n---\nnext",
+                &["first line\nsecond line", "next"],
+            ),
+            ("a\n  ---  \nb", &["a", "b"]),
+            ("a --- b", &["a --- b"]),
+            ("a\n----\nb", &["a\n----\nb"]),
+            ("---\n\n---\nonly\n---\n", &["only"]),
+            ("", &[]),
+            ("\n---\n", &[]),
+        ];
+        for (contents, expected) in cases {
+            assert_eq!(split_thread(contents), *expected, "{contents:?}");
+        }
+    }
+}